@@ -1,7 +1,7 @@
 use sp_core::{Pair, Public, sr25519};
 use node_template_runtime::{
 	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	SudoConfig, SystemConfig, KittiesModuleConfig, WASM_BINARY, Signature
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
@@ -153,5 +153,24 @@ fn testnet_genesis(
 			// Assign network admin rights.
 			key: root_key,
 		}),
+		pallet_kitties: Some(KittiesModuleConfig {
+			// Give each endowed account a pair of kitties to breed.
+			kitties: initial_kitties(&endowed_accounts),
+			// Development and local testnets allow one-shot breeding; production chains
+			// should leave it off and use commit-reveal breeding instead.
+			instant_breed_allowed: true,
+		}),
 	}
 }
+
+/// Generate a male and a female kitty with deterministic DNA for each account.
+fn initial_kitties(accounts: &[AccountId]) -> Vec<(AccountId, [u8; 16])> {
+	accounts.iter().enumerate().flat_map(|(i, account)| {
+		(0u8..2).map(move |gender| {
+			let mut dna = sp_core::blake2_128(&[i as u8, gender]);
+			// The lowest bit of the first DNA byte decides the gender.
+			dna[0] = (dna[0] & !1) | gender;
+			(account.clone(), dna)
+		})
+	}).collect()
+}
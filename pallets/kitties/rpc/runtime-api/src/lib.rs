@@ -0,0 +1,55 @@
+//! Runtime API definition for the kitties pallet.
+//! 前端通过该API查询kitty, 不需要关心 `Kitties`, `AccountKitties` 等存储的具体布局.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// 返回给客户端的kitty信息, dna 以十六进制字符串序列化.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct KittyInfo<AccountId, KittyIndex> {
+	/// kitty id
+	pub id: KittyIndex,
+	/// 当前所有者
+	pub owner: AccountId,
+	/// DNA数据
+	#[cfg_attr(feature = "std", serde(with = "sp_core::bytes"))]
+	pub dna: Vec<u8>,
+	/// 父母kitty
+	pub parents: (Option<KittyIndex>, Option<KittyIndex>),
+	/// 配偶kitty
+	pub spouse: Option<KittyIndex>,
+	/// 代数
+	pub generation: u32,
+	/// 稀有度分数
+	pub rarity: u32,
+	/// 是否由两只kitty融合而来
+	pub fused: bool,
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait KittiesApi<AccountId, KittyIndex, Phenotype> where
+		AccountId: Codec,
+		KittyIndex: Codec,
+		Phenotype: Codec,
+	{
+		/// 查询一只kitty, 不存在时返回None
+		fn kitty(id: KittyIndex) -> Option<KittyInfo<AccountId, KittyIndex>>;
+		/// 某个账号拥有的所有kitty
+		fn kitties_of(owner: AccountId) -> Vec<KittyIndex>;
+		/// kitty的数量
+		fn kitties_count() -> KittyIndex;
+		/// 两只kitty共同的孩子
+		fn children(a: KittyIndex, b: KittyIndex) -> Vec<KittyIndex>;
+		/// kitty的兄弟姐妹
+		fn siblings(id: KittyIndex) -> Vec<KittyIndex>;
+		/// 从dna解码的外观特征
+		fn phenotype(id: KittyIndex) -> Option<Phenotype>;
+	}
+}
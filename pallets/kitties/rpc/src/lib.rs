@@ -0,0 +1,125 @@
+//! RPC interface for the kitties pallet.
+//! 所有方法都以 `kitties_` 为前缀, 可选的 `at` 参数指定查询的区块, 默认为最新区块.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_kitties_rpc_runtime_api::{KittiesApi as KittiesRuntimeApi, KittyInfo};
+
+#[rpc]
+pub trait KittiesApi<BlockHash, AccountId, KittyIndex, Phenotype> {
+	#[rpc(name = "kitties_kitty")]
+	fn kitty(&self, id: KittyIndex, at: Option<BlockHash>) -> Result<Option<KittyInfo<AccountId, KittyIndex>>>;
+
+	#[rpc(name = "kitties_kittiesOf")]
+	fn kitties_of(&self, owner: AccountId, at: Option<BlockHash>) -> Result<Vec<KittyIndex>>;
+
+	#[rpc(name = "kitties_kittiesCount")]
+	fn kitties_count(&self, at: Option<BlockHash>) -> Result<KittyIndex>;
+
+	#[rpc(name = "kitties_children")]
+	fn children(&self, a: KittyIndex, b: KittyIndex, at: Option<BlockHash>) -> Result<Vec<KittyIndex>>;
+
+	#[rpc(name = "kitties_siblings")]
+	fn siblings(&self, id: KittyIndex, at: Option<BlockHash>) -> Result<Vec<KittyIndex>>;
+
+	#[rpc(name = "kitties_phenotype")]
+	fn phenotype(&self, id: KittyIndex, at: Option<BlockHash>) -> Result<Option<Phenotype>>;
+}
+
+/// A struct that implements the [`KittiesApi`].
+pub struct Kitties<C, Block> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> Kitties<C, Block> {
+	/// Create new `Kitties` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error<E: std::fmt::Debug>(e: E) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Unable to query kitties.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId, KittyIndex, Phenotype> KittiesApi<<Block as BlockT>::Hash, AccountId, KittyIndex, Phenotype>
+	for Kitties<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: KittiesRuntimeApi<Block, AccountId, KittyIndex, Phenotype>,
+	AccountId: Codec,
+	KittyIndex: Codec,
+	Phenotype: Codec,
+{
+	fn kitty(
+		&self,
+		id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<KittyInfo<AccountId, KittyIndex>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.kitty(&at, id).map_err(runtime_error)
+	}
+
+	fn kitties_of(&self, owner: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<KittyIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.kitties_of(&at, owner).map_err(runtime_error)
+	}
+
+	fn kitties_count(&self, at: Option<<Block as BlockT>::Hash>) -> Result<KittyIndex> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.kitties_count(&at).map_err(runtime_error)
+	}
+
+	fn children(
+		&self,
+		a: KittyIndex,
+		b: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<KittyIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.children(&at, a, b).map_err(runtime_error)
+	}
+
+	fn siblings(&self, id: KittyIndex, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<KittyIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.siblings(&at, id).map_err(runtime_error)
+	}
+
+	fn phenotype(&self, id: KittyIndex, at: Option<<Block as BlockT>::Hash>) -> Result<Option<Phenotype>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		api.phenotype(&at, id).map_err(runtime_error)
+	}
+}
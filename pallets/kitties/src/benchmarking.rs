@@ -0,0 +1,448 @@
+// pallet_kitties 的 benchmark
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_system::RawOrigin;
+use frame_support::traits::OnInitialize;
+use frame_benchmarking::{benchmarks, account};
+use crate::Module as Kitties;
+
+const SEED: u32 = 0;
+
+// 创建一个有足够余额的账号
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+    let who: T::AccountId = account(name, index, SEED);
+    T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 2u32.into());
+    who
+}
+
+// 为 owner 创建 n 只kitty
+fn create_kitties<T: Trait>(owner: &T::AccountId, n: u32) -> Result<(), &'static str> {
+    for _ in 0..n {
+        Kitties::<T>::create(RawOrigin::Signed(owner.clone()).into())?;
+    }
+    Ok(())
+}
+
+// 直接写入存储创建一只指定性别的kitty, 并为其构造 ancestors 个互不相同的祖先
+// 祖先按层序排成完全二叉树, 位置 i 的父亲和母亲分别位于 2i+1 和 2i+2
+fn kitty_with_ancestors<T: Trait>(
+    owner: &T::AccountId,
+    gender: Gender,
+    ancestors: u32,
+) -> Result<T::KittyIndex, &'static str> {
+    family_member::<T>(owner, gender, 0, ancestors)
+}
+
+fn family_member<T: Trait>(
+    owner: &T::AccountId,
+    gender: Gender,
+    position: u32,
+    ancestors: u32,
+) -> Result<T::KittyIndex, &'static str> {
+    let ancestor_owner: T::AccountId = account("ancestor", position, SEED);
+    let father = if 2 * position + 1 <= ancestors {
+        Some(family_member::<T>(&ancestor_owner, Gender::Male, 2 * position + 1, ancestors)?)
+    } else {
+        None
+    };
+    let mother = if 2 * position + 2 <= ancestors {
+        Some(family_member::<T>(&ancestor_owner, Gender::Female, 2 * position + 2, ancestors)?)
+    } else {
+        None
+    };
+
+    let mut kitty = Kitty::new();
+    kitty.set_parents_ids(father, mother);
+    let kitty_id = Kitties::<T>::next_kitty_id()?;
+    let mut dna = [0u8; 16];
+    if gender == Gender::Female {
+        dna[0] = 1;
+    }
+    kitty.set_kitty_id(kitty_id);
+    kitty.set_dna_data(dna);
+    Kitties::<T>::insert_kitty(owner, kitty_id, kitty)?;
+    Ok(kitty_id)
+}
+
+// 通过 create 创建一只有父母记录和元数据的kitty, 销毁时需要更新孩子记录并退还两份质押
+fn kitty_with_parents<T: Trait>(owner: &T::AccountId) -> Result<T::KittyIndex, &'static str> {
+    let father = kitty_with_ancestors::<T>(owner, Gender::Male, 0)?;
+    let mother = kitty_with_ancestors::<T>(owner, Gender::Female, 0)?;
+    let kitty_id = Kitties::<T>::next_kitty_id()?;
+    create_kitties::<T>(owner, 1)?;
+    crate::Kitties::<T>::mutate(kitty_id, |kitty| {
+        if let Some(kitty) = kitty {
+            kitty.set_parents_ids(Some(father), Some(mother));
+        }
+    });
+    KittiesChildren::<T>::insert(father, mother, sp_std::vec![kitty_id]);
+    set_full_metadata::<T>(owner, kitty_id)?;
+    Ok(kitty_id)
+}
+
+// 设置最长的名字和元数据
+fn set_full_metadata<T: Trait>(owner: &T::AccountId, kitty_id: T::KittyIndex) -> Result<(), &'static str> {
+    let name = sp_std::vec![0u8; T::MaxNameLength::get() as usize];
+    let data = sp_std::vec![0u8; T::MaxMetadataLength::get() as usize];
+    Kitties::<T>::set_metadata(RawOrigin::Signed(owner.clone()).into(), kitty_id, name, data)?;
+    Ok(())
+}
+
+benchmarks! {
+    _ { }
+
+    create {
+        let caller = funded_account::<T>("caller", 0);
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        assert_eq!(Kitties::<T>::owned_kitties_count(&caller), 1);
+    }
+
+    transfer {
+        let caller = funded_account::<T>("caller", 0);
+        let recipient = funded_account::<T>("recipient", 0);
+        create_kitties::<T>(&caller, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&caller)[0];
+    }: _(RawOrigin::Signed(caller.clone()), recipient.clone(), kitty_id)
+    verify {
+        assert_eq!(Kitties::<T>::kitty_owner(kitty_id), Some(recipient));
+    }
+
+    create_batch {
+        let n in 1 .. T::MaxBatchSize::get().min(T::MaxKittiesPerAccount::get());
+        let caller = funded_account::<T>("caller", 0);
+    }: _(RawOrigin::Signed(caller.clone()), n)
+    verify {
+        assert_eq!(Kitties::<T>::owned_kitties_count(&caller), n);
+    }
+
+    transfer_batch {
+        let n in 1 .. T::MaxBatchSize::get().min(T::MaxKittiesPerAccount::get());
+        let caller = funded_account::<T>("caller", 0);
+        let recipient = funded_account::<T>("recipient", 0);
+        create_kitties::<T>(&caller, n)?;
+        let transfers: Vec<_> = Kitties::<T>::kitties_of(&caller)
+            .into_iter()
+            .map(|kitty_id| (recipient.clone(), kitty_id))
+            .collect();
+    }: _(RawOrigin::Signed(caller.clone()), transfers)
+    verify {
+        assert_eq!(Kitties::<T>::owned_kitties_count(&recipient), n);
+    }
+
+    approve {
+        let owner = funded_account::<T>("owner", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let approved: T::AccountId = account("approved", 0, SEED);
+        create_kitties::<T>(&owner, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&owner)[0];
+        Kitties::<T>::set_approval_for_all(RawOrigin::Signed(owner.clone()).into(), caller.clone(), true)?;
+    }: _(RawOrigin::Signed(caller), kitty_id, Some(approved.clone()))
+    verify {
+        assert_eq!(Kitties::<T>::kitty_approval(kitty_id), Some(approved));
+    }
+
+    set_approval_for_all {
+        let caller = funded_account::<T>("caller", 0);
+        let operator: T::AccountId = account("operator", 0, SEED);
+    }: _(RawOrigin::Signed(caller.clone()), operator.clone(), true)
+    verify {
+        assert!(Kitties::<T>::is_approved_for_all(&caller, &operator));
+    }
+
+    transfer_from {
+        let owner = funded_account::<T>("owner", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let recipient = funded_account::<T>("recipient", 0);
+        create_kitties::<T>(&owner, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&owner)[0];
+        Kitties::<T>::approve(RawOrigin::Signed(owner.clone()).into(), kitty_id, Some(caller.clone()))?;
+    }: _(RawOrigin::Signed(caller), owner, recipient.clone(), kitty_id)
+    verify {
+        assert_eq!(Kitties::<T>::kitty_owner(kitty_id), Some(recipient));
+    }
+
+    set_metadata {
+        let b in 0 .. T::MaxNameLength::get().saturating_add(T::MaxMetadataLength::get());
+        let caller = funded_account::<T>("caller", 0);
+        create_kitties::<T>(&caller, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&caller)[0];
+        // 覆盖已有的元数据, 需要退还之前的质押
+        set_full_metadata::<T>(&caller, kitty_id)?;
+        let name_len = b.min(T::MaxNameLength::get());
+        let name = sp_std::vec![0u8; name_len as usize];
+        let data = sp_std::vec![0u8; (b - name_len) as usize];
+    }: _(RawOrigin::Signed(caller), kitty_id, name, data)
+    verify {
+        assert!(Kitties::<T>::kitty_metadata(kitty_id).is_some());
+    }
+
+    clear_metadata {
+        let caller = funded_account::<T>("caller", 0);
+        create_kitties::<T>(&caller, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&caller)[0];
+        set_full_metadata::<T>(&caller, kitty_id)?;
+    }: _(RawOrigin::Signed(caller), kitty_id)
+    verify {
+        assert!(Kitties::<T>::kitty_metadata(kitty_id).is_none());
+    }
+
+    release {
+        let caller = funded_account::<T>("caller", 0);
+        let kitty_id = kitty_with_parents::<T>(&caller)?;
+    }: _(RawOrigin::Signed(caller), kitty_id)
+    verify {
+        assert_eq!(Kitties::<T>::kitty_owner(kitty_id), None);
+    }
+
+    fuse {
+        let caller = funded_account::<T>("caller", 0);
+        let kitty_id_a = kitty_with_parents::<T>(&caller)?;
+        let kitty_id_b = kitty_with_parents::<T>(&caller)?;
+        let new_kitty_id = Kitties::<T>::kitties_count();
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id_a, kitty_id_b)
+    verify {
+        assert_eq!(Kitties::<T>::kitty_owner(new_kitty_id), Some(caller));
+    }
+
+    set_price {
+        let caller = funded_account::<T>("caller", 0);
+        create_kitties::<T>(&caller, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&caller)[0];
+        let price: BalanceOf<T> = 100u32.into();
+    }: _(RawOrigin::Signed(caller), kitty_id, Some(price))
+    verify {
+        assert_eq!(Kitties::<T>::kitty_price(kitty_id), Some(price));
+    }
+
+    create_dutch_auction {
+        let caller = funded_account::<T>("caller", 0);
+        create_kitties::<T>(&caller, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&caller)[0];
+        Kitties::<T>::set_price(RawOrigin::Signed(caller.clone()).into(), kitty_id, Some(100u32.into()))?;
+    }: _(RawOrigin::Signed(caller), kitty_id, 1_000u32.into(), 100u32.into(), 10u32.into())
+    verify {
+        assert!(Kitties::<T>::dutch_auction(kitty_id).is_some());
+    }
+
+    buy {
+        let seller = funded_account::<T>("seller", 0);
+        let caller = funded_account::<T>("caller", 0);
+        create_kitties::<T>(&seller, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&seller)[0];
+        let price: BalanceOf<T> = 100u32.into();
+        Kitties::<T>::set_price(RawOrigin::Signed(seller.clone()).into(), kitty_id, Some(price))?;
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, price)
+    verify {
+        assert_eq!(Kitties::<T>::kitty_owner(kitty_id), Some(caller));
+    }
+
+    create_auction {
+        let caller = funded_account::<T>("caller", 0);
+        create_kitties::<T>(&caller, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&caller)[0];
+    }: _(RawOrigin::Signed(caller), kitty_id, 100u32.into(), 10u32.into())
+    verify {
+        assert!(Kitties::<T>::kitty_auction(kitty_id).is_some());
+    }
+
+    bid {
+        let seller = funded_account::<T>("seller", 0);
+        let bidder = funded_account::<T>("bidder", 0);
+        let caller = funded_account::<T>("caller", 0);
+        create_kitties::<T>(&seller, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&seller)[0];
+        let auction_id = Kitties::<T>::auctions_count();
+        Kitties::<T>::create_auction(RawOrigin::Signed(seller).into(), kitty_id, 100u32.into(), 10u32.into())?;
+        // 超过上一个出价, 需要退还上一个出价人的质押
+        Kitties::<T>::bid(RawOrigin::Signed(bidder).into(), auction_id, 100u32.into())?;
+    }: _(RawOrigin::Signed(caller.clone()), auction_id, 200u32.into())
+    verify {
+        assert_eq!(Kitties::<T>::auctions(auction_id).and_then(|auction| auction.best_bid), Some((caller, 200u32.into())));
+    }
+
+    create_tournament {
+        let caller = funded_account::<T>("caller", 0);
+        let tournament_id = Kitties::<T>::tournaments_count();
+    }: _(RawOrigin::Signed(caller), 100u32.into(), T::MaxTournamentSize::get(), 10u32.into())
+    verify {
+        assert!(Kitties::<T>::tournaments(tournament_id).is_some());
+    }
+
+    enter_tournament {
+        let creator = funded_account::<T>("creator", 0);
+        let caller = funded_account::<T>("caller", 0);
+        let tournament_id = Kitties::<T>::tournaments_count();
+        let max_entrants = T::MaxTournamentSize::get();
+        Kitties::<T>::create_tournament(RawOrigin::Signed(creator).into(), 100u32.into(), max_entrants, 10u32.into())?;
+        // 报名人数越多, 读写的锦标赛记录越大, 只留下最后一个名额
+        Tournaments::<T>::mutate(tournament_id, |tournament| {
+            if let Some(tournament) = tournament {
+                for i in 1..max_entrants {
+                    tournament.entrants.push((account("entrant", i, SEED), i.into()));
+                }
+            }
+        });
+        let kitty_id = kitty_with_ancestors::<T>(&caller, Gender::Male, 0)?;
+    }: _(RawOrigin::Signed(caller), tournament_id, kitty_id)
+    verify {
+        assert_eq!(Kitties::<T>::kitty_tournament(kitty_id), Some(tournament_id));
+    }
+
+    breed {
+        let a in 0 .. Kitties::<T>::max_ancestors();
+        InstantBreedAllowed::put(true);
+        let caller = funded_account::<T>("caller", 0);
+        let father = kitty_with_ancestors::<T>(&caller, Gender::Male, a)?;
+        let mother = kitty_with_ancestors::<T>(&caller, Gender::Female, a)?;
+    }: _(RawOrigin::Signed(caller.clone()), father, mother)
+    verify {
+        assert!(!Kitties::<T>::kitty_ready_at(father).is_zero());
+    }
+
+    set_instant_breed_allowed {
+    }: _(RawOrigin::Root, true)
+    verify {
+        assert!(Kitties::<T>::instant_breed_allowed());
+    }
+
+    commit_breed {
+        let caller = funded_account::<T>("caller", 0);
+        // 覆盖之前的承诺, 需要没收之前的保证金
+        let old_commitment = Kitties::<T>::breed_commitment_hash(0u32.into(), 1u32.into(), &[1u8; 32]);
+        Kitties::<T>::commit_breed(RawOrigin::Signed(caller.clone()).into(), old_commitment)?;
+        let commitment = Kitties::<T>::breed_commitment_hash(0u32.into(), 1u32.into(), &[0u8; 32]);
+    }: _(RawOrigin::Signed(caller.clone()), commitment)
+    verify {
+        assert!(Kitties::<T>::breed_commitment(&caller).is_some());
+    }
+
+    reveal_breed {
+        let a in 0 .. Kitties::<T>::max_ancestors();
+        let caller = funded_account::<T>("caller", 0);
+        let sire_owner = funded_account::<T>("sire_owner", 0);
+        let mother = kitty_with_ancestors::<T>(&caller, Gender::Female, a)?;
+        let father = kitty_with_ancestors::<T>(&sire_owner, Gender::Male, a)?;
+        // 使用别人的kitty配种, 需要支付配种费用
+        Kitties::<T>::offer_siring(RawOrigin::Signed(sire_owner).into(), father, 100u32.into())?;
+        let salt = [1u8; 32];
+        let commitment = Kitties::<T>::breed_commitment_hash(mother, father, &salt);
+        Kitties::<T>::commit_breed(RawOrigin::Signed(caller.clone()).into(), commitment)?;
+        let (_, reveal_at, _) = Kitties::<T>::breed_commitment(&caller).ok_or("commitment not stored")?;
+        frame_system::Module::<T>::set_block_number(reveal_at);
+        Kitties::<T>::on_initialize(reveal_at);
+    }: _(RawOrigin::Signed(caller.clone()), mother, father, salt, 100u32.into())
+    verify {
+        assert!(!Kitties::<T>::kitty_ready_at(mother).is_zero());
+        assert!(Kitties::<T>::breed_commitment(&caller).is_none());
+    }
+
+    offer_siring {
+        let caller = funded_account::<T>("caller", 0);
+        create_kitties::<T>(&caller, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&caller)[0];
+        let fee: BalanceOf<T> = 100u32.into();
+    }: _(RawOrigin::Signed(caller), kitty_id, fee)
+    verify {
+        assert_eq!(Kitties::<T>::siring_offer(kitty_id), Some(fee));
+    }
+
+    cancel_siring_offer {
+        let caller = funded_account::<T>("caller", 0);
+        create_kitties::<T>(&caller, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&caller)[0];
+        Kitties::<T>::offer_siring(RawOrigin::Signed(caller.clone()).into(), kitty_id, 100u32.into())?;
+    }: _(RawOrigin::Signed(caller), kitty_id)
+    verify {
+        assert_eq!(Kitties::<T>::siring_offer(kitty_id), None);
+    }
+
+    breed_with {
+        let a in 0 .. Kitties::<T>::max_ancestors();
+        InstantBreedAllowed::put(true);
+        let caller = funded_account::<T>("caller", 0);
+        let sire_owner = funded_account::<T>("sire_owner", 0);
+        let mother = kitty_with_ancestors::<T>(&caller, Gender::Female, a)?;
+        let father = kitty_with_ancestors::<T>(&sire_owner, Gender::Male, a)?;
+        Kitties::<T>::offer_siring(RawOrigin::Signed(sire_owner).into(), father, 100u32.into())?;
+    }: _(RawOrigin::Signed(caller), mother, father, 100u32.into())
+    verify {
+        assert!(!Kitties::<T>::kitty_ready_at(mother).is_zero());
+    }
+
+    lease {
+        let caller = funded_account::<T>("caller", 0);
+        let lessee: T::AccountId = account("lessee", 0, SEED);
+        create_kitties::<T>(&caller, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&caller)[0];
+    }: _(RawOrigin::Signed(caller), kitty_id, lessee, 10u32.into(), 100u32.into())
+    verify {
+        assert!(Kitties::<T>::lease_offer(kitty_id).is_some());
+    }
+
+    cancel_lease_offer {
+        let caller = funded_account::<T>("caller", 0);
+        let lessee: T::AccountId = account("lessee", 0, SEED);
+        create_kitties::<T>(&caller, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&caller)[0];
+        Kitties::<T>::lease(RawOrigin::Signed(caller.clone()).into(), kitty_id, lessee, 10u32.into(), 100u32.into())?;
+    }: _(RawOrigin::Signed(caller), kitty_id)
+    verify {
+        assert!(Kitties::<T>::lease_offer(kitty_id).is_none());
+    }
+
+    accept_lease {
+        let lessor = funded_account::<T>("lessor", 0);
+        let caller = funded_account::<T>("caller", 0);
+        create_kitties::<T>(&lessor, 1)?;
+        let kitty_id = Kitties::<T>::kitties_of(&lessor)[0];
+        let price: BalanceOf<T> = 100u32.into();
+        Kitties::<T>::lease(RawOrigin::Signed(lessor).into(), kitty_id, caller.clone(), 10u32.into(), price)?;
+    }: _(RawOrigin::Signed(caller.clone()), kitty_id, price)
+    verify {
+        assert!(Kitties::<T>::kitty_lease(kitty_id).is_some());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{new_test_ext, Test};
+    use frame_support::assert_ok;
+
+    #[test]
+    fn test_benchmarks() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_create::<Test>());
+            assert_ok!(test_benchmark_transfer::<Test>());
+            assert_ok!(test_benchmark_create_batch::<Test>());
+            assert_ok!(test_benchmark_transfer_batch::<Test>());
+            assert_ok!(test_benchmark_approve::<Test>());
+            assert_ok!(test_benchmark_set_approval_for_all::<Test>());
+            assert_ok!(test_benchmark_transfer_from::<Test>());
+            assert_ok!(test_benchmark_set_metadata::<Test>());
+            assert_ok!(test_benchmark_clear_metadata::<Test>());
+            assert_ok!(test_benchmark_release::<Test>());
+            assert_ok!(test_benchmark_fuse::<Test>());
+            assert_ok!(test_benchmark_set_price::<Test>());
+            assert_ok!(test_benchmark_create_dutch_auction::<Test>());
+            assert_ok!(test_benchmark_buy::<Test>());
+            assert_ok!(test_benchmark_create_auction::<Test>());
+            assert_ok!(test_benchmark_bid::<Test>());
+            assert_ok!(test_benchmark_create_tournament::<Test>());
+            assert_ok!(test_benchmark_enter_tournament::<Test>());
+            assert_ok!(test_benchmark_breed::<Test>());
+            assert_ok!(test_benchmark_set_instant_breed_allowed::<Test>());
+            assert_ok!(test_benchmark_commit_breed::<Test>());
+            assert_ok!(test_benchmark_reveal_breed::<Test>());
+            assert_ok!(test_benchmark_offer_siring::<Test>());
+            assert_ok!(test_benchmark_cancel_siring_offer::<Test>());
+            assert_ok!(test_benchmark_breed_with::<Test>());
+            assert_ok!(test_benchmark_lease::<Test>());
+            assert_ok!(test_benchmark_cancel_lease_offer::<Test>());
+            assert_ok!(test_benchmark_accept_lease::<Test>());
+        });
+    }
+}
@@ -0,0 +1,66 @@
+// 可替换的遗传规则, 由运行时通过 Trait::Genetics 选择
+//
+// 随机数由 pallet 提供(一步繁殖或提交-揭示), 遗传规则只负责把随机数和父母的dna
+// 映射为新的dna, 必须是确定性的纯函数.
+
+use crate::phenotype;
+
+pub trait Genetics {
+    // 由随机种子生成初代kitty的dna
+    fn create(seed: &[u8; 16]) -> [u8; 16] {
+        *seed
+    }
+    // 由父母的dna和随机值 selector 组合出孩子的dna, 融合时为被融合的两只kitty
+    fn recombine(father: &[u8; 16], mother: &[u8; 16], selector: &[u8; 16]) -> [u8; 16];
+}
+
+// 均匀交叉: 每一位按 selector 中对应的位取自父亲(1)或母亲(0)
+pub struct UniformCrossover;
+
+impl Genetics for UniformCrossover {
+    fn recombine(father: &[u8; 16], mother: &[u8; 16], selector: &[u8; 16]) -> [u8; 16] {
+        let mut new_dna = [0u8; 16];
+        for i in 0..father.len() {
+            new_dna[i] = combine_dna(father[i], mother[i], selector[i]);
+        }
+        new_dna
+    }
+}
+
+// 单点交叉: 由 selector 第一个字节选出 1 到 127 之间的切点,
+// 从最高位开始, 切点之前的位取自父亲, 之后的取自母亲
+pub struct SinglePointCrossover;
+
+impl Genetics for SinglePointCrossover {
+    fn recombine(father: &[u8; 16], mother: &[u8; 16], selector: &[u8; 16]) -> [u8; 16] {
+        let cut = 1 + (selector[0] % 127) as usize;
+        let mut new_dna = [0u8; 16];
+        for i in 0..father.len() {
+            let start = i * 8;
+            let mask = if cut >= start + 8 {
+                0xff
+            } else if cut <= start {
+                0
+            } else {
+                0xff << (8 - (cut - start))
+            };
+            new_dna[i] = combine_dna(father[i], mother[i], mask);
+        }
+        new_dna
+    }
+}
+
+// 孟德尔遗传: 外观特征基因按等位基因遗传(见 phenotype), 其余字节与均匀交叉相同
+pub struct Mendelian;
+
+impl Genetics for Mendelian {
+    fn recombine(father: &[u8; 16], mother: &[u8; 16], selector: &[u8; 16]) -> [u8; 16] {
+        let mut new_dna = UniformCrossover::recombine(father, mother, selector);
+        phenotype::inherit_genes(father, mother, selector, &mut new_dna);
+        new_dna
+    }
+}
+
+fn combine_dna(dna1: u8, dna2: u8, selector: u8) -> u8 {
+    (selector & dna1) | (!selector & dna2)
+}
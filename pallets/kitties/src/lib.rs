@@ -2,14 +2,42 @@
 
 
 use codec::{Encode, Decode};
-use frame_support::{Parameter,decl_module, decl_storage,decl_event, decl_error, traits::Get, ensure, StorageValue, StorageMap, traits::Randomness, sp_std::prelude::*};
-use sp_io::hashing::blake2_128;
-use frame_system::ensure_signed;
-use sp_runtime::{DispatchError, traits::AtLeast32BitUnsigned};
-use sp_runtime::traits::Bounded;
+use frame_support::{Parameter, weights::Weight, transactional, decl_module, decl_storage,decl_event, decl_error, traits::Get, ensure, StorageValue, StorageMap, StorageDoubleMap, IterableStorageDoubleMap, traits::Randomness, sp_std::prelude::*};
+use sp_io::hashing::{blake2_128, blake2_256};
+use frame_system::{ensure_signed, ensure_root};
+use sp_runtime::{DispatchError, DispatchResult, Perbill, Permill, traits::AtLeast32BitUnsigned};
+use sp_runtime::traits::{Bounded, Zero, Saturating, Hash};
 use sp_std::vec;
+use sp_std::collections::btree_set::BTreeSet;
 use frame_support::traits::Currency;
 use frame_support::traits::ReservableCurrency;
+use frame_support::traits::{ExistenceRequirement, BalanceStatus};
+
+mod phenotype;
+mod stats;
+pub mod genetics;
+pub use genetics::Genetics;
+mod migrations;
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub use phenotype::{Phenotype, BodyColor, EyeColor, Pattern, Accessory, RarityTier};
+pub use stats::Stats;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+// kitty 的性别, 由 dna 第一个字节的最低位决定
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gender {
+    Male,
+    Female,
+}
 
 #[derive(Encode, Decode, Debug, Clone)]
 pub struct Kitty<T> {
@@ -21,10 +49,151 @@ pub struct Kitty<T> {
     spouse_id: Option<T>,
     // DNA数据
     dna_data: [u8; 16],
+    // 代数, 初代kitty为0, 后代为父母中较大的代数加1
+    generation: u32,
+    // 繁殖时dna是否发生了突变
+    mutated: bool,
+    // 稀有度分数, 由dna和代数计算, 设置dna或代数时更新
+    rarity: u32,
+}
+
+// kitty 的创建质押由谁承担
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepositPolicy {
+    // 质押一直由创建者承担, 转移后也不变
+    CreatorPays,
+    // 质押随kitty转移, 由当前所有者承担
+    OwnerPays,
 }
 
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
 
+pub type AuctionIndex = u32;
+
+pub type TournamentIndex = u32;
+
+// 存储布局的版本, 用于 on_runtime_upgrade 判断是否需要迁移
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Releases {
+    // OwnedKitties 为 AccountId => Vec<KittyIndex>, 并用 KittiesBrother 记录兄弟姐妹
+    V1,
+    // 所有权改为 AccountKitties 双键映射加 OwnedKittiesCount 计数, 兄弟姐妹由 KittiesChildren 计算
+    V2,
+    // Kitty 增加稀有度分数
+    V3,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1
+    }
+}
+
+// 英式拍卖, 出价只升不降, 到期后由最高出价人获得kitty
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+pub struct Auction<AccountId, Balance, BlockNumber, KittyIndex> {
+    // 拍卖的kitty
+    pub kitty_id: KittyIndex,
+    // 卖家
+    pub seller: AccountId,
+    // 起拍价
+    pub min_bid: Balance,
+    // 当前最高出价人及出价, 出价在出价人账户中质押
+    pub best_bid: Option<(AccountId, Balance)>,
+    // 结束区块, 在该区块的 on_finalize 中结算
+    pub end: BlockNumber,
+}
+
+// 荷兰式拍卖, 价格从 start_price 在 duration 个区块内线性下降到 floor_price, 之后保持底价
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+pub struct DutchAuction<Balance, BlockNumber> {
+    // 起始价格
+    pub start_price: Balance,
+    // 底价
+    pub floor_price: Balance,
+    // 开始区块
+    pub start: BlockNumber,
+    // 降价持续的区块数
+    pub duration: BlockNumber,
+}
+
+// kitty 的名字和元数据(如图片地址), 由设置者按字节支付质押
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+pub struct KittyMetadata<AccountId, Balance> {
+    // 名字
+    pub name: vec::Vec<u8>,
+    // 元数据
+    pub data: vec::Vec<u8>,
+    // 质押人, kitty转移后清除元数据时仍退还给该账号
+    pub depositor: AccountId,
+    // 质押金额
+    pub deposit: Balance,
+}
+
+// 出租邀约, 指定的承租人接受后开始租期
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+pub struct LeaseOffer<AccountId, Balance, BlockNumber> {
+    // 承租人
+    pub lessee: AccountId,
+    // 租期区块数
+    pub duration: BlockNumber,
+    // 租金, 接受时支付给出租人
+    pub price: Balance,
+}
+
+// 租约, 租期内kitty归承租人所有, 可以繁殖但不能转移, 到期后归还出租人
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+pub struct Lease<AccountId, BlockNumber> {
+    // 出租人
+    pub lessor: AccountId,
+    // 承租人
+    pub lessee: AccountId,
+    // 到期区块, 在该区块的 on_initialize 中归还
+    pub end: BlockNumber,
+}
+
+// 锦标赛, 报名截止后每个区块进行一轮淘汰赛, 最后的胜者赢得所有报名费
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+pub struct Tournament<AccountId, Balance, BlockNumber, KittyIndex> {
+    // 创建者
+    pub creator: AccountId,
+    // 创建者质押的金额, 锦标赛结束或取消时退还
+    pub deposit: Balance,
+    // 报名费, 在报名者账户中质押
+    pub entry_fee: Balance,
+    // 最多参赛的kitty数量
+    pub max_entrants: u32,
+    // 报名截止区块, 在该区块的 on_finalize 中进行第一轮
+    pub start: BlockNumber,
+    // 所有参赛的kitty及其所有者, 按报名顺序排列
+    pub entrants: vec::Vec<(AccountId, KittyIndex)>,
+    // 仍未被淘汰的参赛者在 entrants 中的位置, 开赛前为空
+    pub remaining: vec::Vec<u32>,
+    // 已经进行的轮数
+    pub round: u32,
+}
+
+type TournamentOf<T> = Tournament<
+    <T as frame_system::Trait>::AccountId,
+    BalanceOf<T>,
+    <T as frame_system::Trait>::BlockNumber,
+    <T as Trait>::KittyIndex,
+>;
+
+type LeaseOfferOf<T> = LeaseOffer<<T as frame_system::Trait>::AccountId, BalanceOf<T>, <T as frame_system::Trait>::BlockNumber>;
+type LeaseOf<T> = Lease<<T as frame_system::Trait>::AccountId, <T as frame_system::Trait>::BlockNumber>;
+
+type KittyMetadataOf<T> = KittyMetadata<<T as frame_system::Trait>::AccountId, BalanceOf<T>>;
+
+type DutchAuctionOf<T> = DutchAuction<BalanceOf<T>, <T as frame_system::Trait>::BlockNumber>;
+
+type AuctionOf<T> = Auction<
+    <T as frame_system::Trait>::AccountId,
+    BalanceOf<T>,
+    <T as frame_system::Trait>::BlockNumber,
+    <T as Trait>::KittyIndex,
+>;
+
 pub trait Trait: frame_system::Trait {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
     type Randomness: Randomness<Self::Hash>;
@@ -33,16 +202,62 @@ pub trait Trait: frame_system::Trait {
     type NewKittyReserve: Get<BalanceOf<Self>>;
     // Currency 类型，用于质押等资产相关的操作
     type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
+    // kitty 转移时质押是否随之转移给新的所有者
+    type DepositPolicy: Get<DepositPolicy>;
+    // 设置元数据的基础质押
+    type MetadataDepositBase: Get<BalanceOf<Self>>;
+    // 名字和元数据每个字节的质押
+    type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+    // 名字的最大长度
+    type MaxNameLength: Get<u32>;
+    // 元数据的最大长度
+    type MaxMetadataLength: Get<u32>;
+    // 每个账号最多拥有的kitty数量
+    type MaxKittiesPerAccount: Get<u32>;
+    // create_batch 和 transfer_batch 一次最多处理的kitty数量
+    type MaxBatchSize: Get<u32>;
+    // 同一个区块最多结束的拍卖数量, 限制 on_finalize 结算的工作量
+    type MaxAuctionsPerBlock: Get<u32>;
+    // 同一个区块最多结束的租约数量, 限制 on_initialize 归还kitty的工作量
+    type MaxLeasesPerBlock: Get<u32>;
+    // 一场锦标赛最多参赛的kitty数量
+    type MaxTournamentSize: Get<u32>;
+    // 同一个区块最多进行的锦标赛数量, 限制 on_finalize 对战的工作量
+    type MaxTournamentsPerBlock: Get<u32>;
+    // 创建锦标赛需要质押的代币
+    type TournamentDeposit: Get<BalanceOf<Self>>;
+    // 近亲繁殖检查的深度: 0 只禁止父母/子女和兄弟姐妹, 1 同时禁止堂表兄弟姐妹, 以此类推
+    // 每增加一层, 需要读取的祖先数量翻倍, 应保持较小的值
+    type InbreedingDepth: Get<u32>;
+    // 怀孕期, breed 之后经过该区块数kitty才出生, 至少为1个区块
+    type GestationPeriod: Get<Self::BlockNumber>;
+    // 初代kitty出生后的繁殖冷却区块数, 随代数增长
+    type BreedCooldown: Get<Self::BlockNumber>;
+    // 繁殖时每个dna字节随机翻转一位的概率
+    type MutationRate: Get<Permill>;
+    // 生成和组合dna的遗传规则
+    type Genetics: Genetics;
+    // 提交繁殖承诺后第 RevealDelay 个区块(至少为1)是揭示区块, 揭示时使用该区块开始时记录的随机数
+    type RevealDelay: Get<Self::BlockNumber>;
+    // 揭示区块之后还可以揭示的区块数, 超过后承诺失效, 需要重新提交
+    type RevealWindow: Get<Self::BlockNumber>;
+    // 提交繁殖承诺时质押的保证金, 揭示后退还, 过期未揭示或被新的承诺覆盖时没收
+    type BreedBond: Get<BalanceOf<Self>>;
+    // 可调用函数的权重
+    type WeightInfo: WeightInfo;
 }
 
 
-impl<T: std::cmp::PartialEq> Kitty<T>  {
+impl<T: Copy> Kitty<T>  {
     pub fn new() -> Self {
         Self {
             kitty_id: None, // kittyid
             parents_ids:(None, None), // 父母数据
             spouse_id: None, //配偶
-            dna_data:[0; 16]
+            dna_data:[0; 16],
+            generation: 0,
+            mutated: false,
+            rarity: 0,
         }
     }
     // 设置kittyid
@@ -52,6 +267,7 @@ impl<T: std::cmp::PartialEq> Kitty<T>  {
     // 设置dna
     pub fn set_dna_data(& mut self, dna_data: [u8; 16]) {
         self.dna_data = dna_data;
+        self.update_rarity();
     }
     // 设置配偶
     pub fn set_spouse_id(&mut self, kitty_id: T) {
@@ -61,6 +277,59 @@ impl<T: std::cmp::PartialEq> Kitty<T>  {
     pub fn set_parents_ids(&mut self, kitty_id1: Option<T>, kitty_id2: Option<T>) {
         self.parents_ids = (kitty_id1, kitty_id2);
     }
+    // 设置代数
+    pub fn set_generation(&mut self, generation: u32) {
+        self.generation = generation;
+        self.update_rarity();
+    }
+    // 获取代数
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+    // 重新计算稀有度
+    fn update_rarity(&mut self) {
+        self.rarity = phenotype::rarity_score(&self.dna_data, self.generation);
+    }
+    // 获取稀有度分数
+    pub fn rarity(&self) -> u32 {
+        self.rarity
+    }
+    // 设置突变标记
+    pub fn set_mutated(&mut self, mutated: bool) {
+        self.mutated = mutated;
+    }
+    // dna是否发生了突变
+    pub fn is_mutated(&self) -> bool {
+        self.mutated
+    }
+    // 获取kittyid
+    pub fn kitty_id(&self) -> Option<T> {
+        self.kitty_id
+    }
+    // 获取dna
+    pub fn dna_data(&self) -> [u8; 16] {
+        self.dna_data
+    }
+    // 获取性别
+    pub fn gender(&self) -> Gender {
+        if self.dna_data[0] & 1 == 0 {
+            Gender::Male
+        } else {
+            Gender::Female
+        }
+    }
+    // 从dna解码外观特征
+    pub fn phenotype(&self) -> Phenotype {
+        phenotype::phenotype(&self.dna_data)
+    }
+    // 获取配偶
+    pub fn spouse_id(&self) -> Option<T> {
+        self.spouse_id
+    }
+    // 获取父母
+    pub fn parents_ids(&self) -> (Option<T>, Option<T>) {
+        self.parents_ids
+    }
 }
 
 
@@ -72,16 +341,89 @@ decl_storage! {
 		pub KittiesCount get(fn kitties_count): T::KittyIndex;
         // 记录每一只kitty的拥有者
 		pub KittyOwner get(fn kitty_owner): map hasher(blake2_128_concat) T::KittyIndex => Option<T::AccountId>;
-        // 记录某个账号拥有的所有kitty
-        pub OwnedKitties get(fn owned_kitties): map hasher(blake2_128_concat) T::AccountId => vec::Vec<T::KittyIndex>;
+        // 记录某个账号拥有的所有kitty, 转移时只需增删一项
+        pub AccountKitties get(fn account_kitties): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::KittyIndex => Option<()>;
+        // 记录某个账号拥有的kitty数量
+        pub OwnedKittiesCount get(fn owned_kitties_count): map hasher(blake2_128_concat) T::AccountId => u32;
 
         // 通过一个double map 可以从父母的任何一方的index映射到孩子的index. 使用Vec<KittyIndex>是因为同一对父母可能产生多个孩子
         // 在breed是更新
         pub KittiesChildren get(fn kitty_children): double_map hasher(blake2_128_concat) T::KittyIndex,  hasher(blake2_128_concat) T::KittyIndex => vec::Vec<T::KittyIndex>;
 
-        // 可以通过 Kitties parents和kitties children 得到一个kittyIndex 到 bother(Vec<KittyIndex>)的一个映射关系
-        // 在breed是更新
-        pub KittiesBrother get(fn kitty_brother): map hasher(blake2_128_concat) T::KittyIndex => vec::Vec<T::KittyIndex>;
+        // 每个区块出生的kitty及繁殖时的质押, 在 on_initialize 中分配 kitty id
+        pub PendingBirths get(fn pending_births): map hasher(twox_64_concat) T::BlockNumber => vec::Vec<(T::AccountId, Kitty<T::KittyIndex>, BalanceOf<T>)>;
+        // kitty的名字和元数据, 单独存储以免读取kitty时加载
+        pub Metadata get(fn kitty_metadata): map hasher(blake2_128_concat) T::KittyIndex => Option<KittyMetadataOf<T>>;
+        // 由两只kitty融合而来的kitty, 它的 parents_ids 记录的是被融合的kitty
+        pub FusedKitties get(fn is_fused): map hasher(blake2_128_concat) T::KittyIndex => bool;
+        // 每只kitty的质押人及质押金额, 转移后仍退还给原质押人
+        pub KittyDeposits get(fn kitty_deposit): map hasher(blake2_128_concat) T::KittyIndex => Option<(T::AccountId, BalanceOf<T>)>;
+        // kitty 可以再次繁殖的区块, 怀孕期和冷却期内不能繁殖
+        pub KittyReadyAt get(fn kitty_ready_at): map hasher(blake2_128_concat) T::KittyIndex => T::BlockNumber;
+        // 每个账号尚未揭示的繁殖承诺, 揭示区块及质押的保证金
+        pub BreedCommitments get(fn breed_commitment): map hasher(blake2_128_concat) T::AccountId => Option<(T::Hash, T::BlockNumber, BalanceOf<T>)>;
+        // 在该区块过期的繁殖承诺的提交者, 这些承诺都在同一个区块提交
+        pub BreedCommitmentsExpiringAt get(fn breed_commitments_expiring_at): map hasher(twox_64_concat) T::BlockNumber => vec::Vec<T::AccountId>;
+        // 有繁殖承诺需要在该区块记录随机数
+        pub RevealScheduled get(fn reveal_scheduled): map hasher(twox_64_concat) T::BlockNumber => bool;
+        // 揭示区块开始时记录的随机数, 揭示期结束后删除
+        pub RevealRandomness get(fn reveal_randomness): map hasher(twox_64_concat) T::BlockNumber => Option<T::Hash>;
+
+        // 被授权转移某只kitty的账号, 转移后清除
+        pub KittyApprovals get(fn kitty_approval): map hasher(blake2_128_concat) T::KittyIndex => Option<T::AccountId>;
+        // 被授权转移某个账号所有kitty的操作员
+        pub OperatorApprovals get(fn is_approved_for_all): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::AccountId => bool;
+
+        // 提供配种服务的kitty及配种费用, 转移后清除
+        pub SiringOffers get(fn siring_offer): map hasher(blake2_128_concat) T::KittyIndex => Option<BalanceOf<T>>;
+
+        // 挂单出售的kitty及其一口价, 转移后清除
+        pub KittyPrices get(fn kitty_price): map hasher(blake2_128_concat) T::KittyIndex => Option<BalanceOf<T>>;
+        // 荷兰式拍卖的kitty, 与一口价互斥, 转移后清除
+        pub DutchAuctions get(fn dutch_auction): map hasher(blake2_128_concat) T::KittyIndex => Option<DutchAuctionOf<T>>;
+
+        // 记录拍卖的数量, 同时作为下一个拍卖的id
+        pub AuctionsCount get(fn auctions_count): AuctionIndex;
+        // 进行中的拍卖
+        pub Auctions get(fn auctions): map hasher(blake2_128_concat) AuctionIndex => Option<AuctionOf<T>>;
+        // 每个区块需要结算的拍卖
+        pub AuctionsEndingAt get(fn auctions_ending_at): map hasher(twox_64_concat) T::BlockNumber => vec::Vec<AuctionIndex>;
+        // 正在拍卖的kitty, 拍卖期间不能转移和繁殖
+        pub KittyAuction get(fn kitty_auction): map hasher(blake2_128_concat) T::KittyIndex => Option<AuctionIndex>;
+
+        // 出租邀约, 转移后清除
+        pub LeaseOffers get(fn lease_offer): map hasher(blake2_128_concat) T::KittyIndex => Option<LeaseOfferOf<T>>;
+        // 出租中的kitty, 租期内不能转移, 出售和销毁
+        pub Leases get(fn kitty_lease): map hasher(blake2_128_concat) T::KittyIndex => Option<LeaseOf<T>>;
+        // 每个区块到期的租约
+        pub LeasesEndingAt get(fn leases_ending_at): map hasher(twox_64_concat) T::BlockNumber => vec::Vec<T::KittyIndex>;
+
+        // 记录锦标赛的数量, 同时作为下一个锦标赛的id
+        pub TournamentsCount get(fn tournaments_count): TournamentIndex;
+        // 报名中和进行中的锦标赛
+        pub Tournaments get(fn tournaments): map hasher(blake2_128_concat) TournamentIndex => Option<TournamentOf<T>>;
+        // 每个区块需要进行下一轮的锦标赛
+        pub TournamentsAt get(fn tournaments_at): map hasher(twox_64_concat) T::BlockNumber => vec::Vec<TournamentIndex>;
+        // 参加锦标赛的kitty, 锦标赛结束前不能转移和繁殖
+        pub KittyTournament get(fn kitty_tournament): map hasher(blake2_128_concat) T::KittyIndex => Option<TournamentIndex>;
+
+        // 存储布局的版本
+        pub StorageVersion get(fn storage_version) build(|_| Releases::V3): Releases;
+        // 是否允许不经过提交-揭示的一步繁殖, 一步繁殖的dna可以被出块者预测, 只应在开发链上开启
+        pub InstantBreedAllowed get(fn instant_breed_allowed) config(): bool;
+    }
+    add_extra_genesis {
+        // 创世kitty的所有者和dna, 创世kitty不需要质押
+        config(kitties): vec::Vec<(T::AccountId, [u8; 16])>;
+        build(|config: &GenesisConfig<T>| {
+            for (owner, dna) in config.kitties.iter() {
+                let kitty_id = <Module<T>>::next_kitty_id().expect("genesis kitties count overflow");
+                let mut kitty = Kitty::new();
+                kitty.set_kitty_id(kitty_id);
+                kitty.set_dna_data(*dna);
+                <Module<T>>::insert_kitty(owner, kitty_id, kitty).expect("genesis kitties exceed MaxKittiesPerAccount");
+            }
+        });
     }
 }
 decl_error! {
@@ -89,17 +431,119 @@ decl_error! {
 		KittiesCountOverflow,
 		InvalidKittyId,
 		RequireDifferentParent,
+        RelatedParents,
+        SameGender,
+        KittyBusy,
+        NotApproved,
+        NoSiringOffer,
         NotKittyOwner,
         MoneyNotEnough,
+        NotForSale,
+        PriceTooHigh,
+        BuyOwnKitty,
+        KittyLocked,
+        AuctionsCountOverflow,
+        InvalidAuctionId,
+        InvalidAuctionDuration,
+        TooManyAuctionsEnding,
+        AuctionEnded,
+        BidOwnAuction,
+        BidTooLow,
+        InvalidPriceRange,
+        NameTooLong,
+        MetadataTooLong,
+        MetadataNotFound,
+        TooManyKitties,
+        BatchTooLarge,
+        KittyLeased,
+        LeaseOwnKitty,
+        InvalidLeaseDuration,
+        NoLeaseOffer,
+        NotLessee,
+        TooManyLeasesEnding,
+        TournamentsCountOverflow,
+        InvalidTournamentId,
+        InvalidTournamentSize,
+        InvalidTournamentStart,
+        RegistrationClosed,
+        TournamentFull,
+        TooManyTournaments,
+        InstantBreedDisabled,
+        NoBreedCommitment,
+        InvalidReveal,
+        RevealTooEarly,
+        CommitmentExpired,
 	}
 }
 decl_event!(
 	pub enum Event<T> 
         where 
             AccountId = <T as frame_system::Trait>::AccountId,
-            KittyIndex = <T as Trait>::KittyIndex {
-		Created(AccountId, KittyIndex),
+            KittyIndex = <T as Trait>::KittyIndex,
+            Balance = BalanceOf<T>,
+            BlockNumber = <T as frame_system::Trait>::BlockNumber,
+            Hash = <T as frame_system::Trait>::Hash {
+        // 创建kitty [owner, kitty_id, generation, rarity]
+		Created(AccountId, KittyIndex, u32, u32),
         Transfered(AccountId, AccountId, KittyIndex),
+        // 授权或撤销授权转移kitty [owner, kitty_id, approved]
+        Approval(AccountId, KittyIndex, Option<AccountId>),
+        // 授权或撤销操作员 [owner, operator, approved]
+        ApprovalForAll(AccountId, AccountId, bool),
+        // 提供或撤销配种服务 [owner, kitty_id, fee]
+        SiringOffered(AccountId, KittyIndex, Option<Balance>),
+        // 支付配种费用 [payer, sire_owner, sire_id, fee]
+        SiringFeePaid(AccountId, AccountId, KittyIndex, Balance),
+        // 设置名字和元数据 [owner, kitty_id]
+        MetadataSet(AccountId, KittyIndex),
+        // 清除名字和元数据 [owner, kitty_id]
+        MetadataCleared(AccountId, KittyIndex),
+        // 两只kitty融合为一只新的kitty [owner, kitty_id_a, kitty_id_b, new_kitty_id]
+        Fused(AccountId, KittyIndex, KittyIndex, KittyIndex),
+        // kitty 被销毁 [owner, kitty_id]
+        Released(AccountId, KittyIndex),
+        // 开始怀孕 [owner, kitty_id_1, kitty_id_2, due]
+        BreedingStarted(AccountId, KittyIndex, KittyIndex, BlockNumber),
+        // 提交繁殖承诺 [owner, commitment, reveal_at]
+        BreedCommitted(AccountId, Hash, BlockNumber),
+        // 开启或关闭一步繁殖 [allowed]
+        InstantBreedAllowedSet(bool),
+        // 繁殖承诺过期未揭示或被覆盖, 保证金被没收 [owner, bond]
+        BreedBondForfeited(AccountId, Balance),
+        // 怀孕到期, kitty 出生 [owner, kitty_id, generation, rarity]
+        Born(AccountId, KittyIndex, u32, u32),
+        // 所有者的kitty数量已达上限或kitty数量溢出, 无法出生, 退还质押 [owner, father, mother]
+        BirthFailed(AccountId, KittyIndex, KittyIndex),
+        // 设置或撤销一口价 [owner, kitty_id, price]
+        PriceSet(AccountId, KittyIndex, Option<Balance>),
+        // 创建荷兰式拍卖 [owner, kitty_id, start_price, floor_price, duration]
+        DutchAuctionCreated(AccountId, KittyIndex, Balance, Balance, BlockNumber),
+        // kitty 被买走 [buyer, seller, kitty_id, price]
+        Sold(AccountId, AccountId, KittyIndex, Balance),
+        // 提供或撤销出租邀约 [owner, kitty_id, (lessee, duration, price)]
+        LeaseOffered(AccountId, KittyIndex, Option<(AccountId, BlockNumber, Balance)>),
+        // 开始租期 [lessor, lessee, kitty_id, price, end]
+        LeaseStarted(AccountId, AccountId, KittyIndex, Balance, BlockNumber),
+        // 租期结束, kitty 归还出租人 [lessor, lessee, kitty_id]
+        LeaseEnded(AccountId, AccountId, KittyIndex),
+        // 创建拍卖 [seller, auction_id, kitty_id, min_bid, end]
+        AuctionCreated(AccountId, AuctionIndex, KittyIndex, Balance, BlockNumber),
+        // 出价 [bidder, auction_id, amount]
+        BidPlaced(AccountId, AuctionIndex, Balance),
+        // 拍卖成交 [auction_id, kitty_id, winner, amount]
+        AuctionSettled(AuctionIndex, KittyIndex, AccountId, Balance),
+        // 拍卖到期无人出价, kitty 留在卖家手中 [auction_id, kitty_id]
+        AuctionCancelled(AuctionIndex, KittyIndex),
+        // 创建锦标赛 [creator, tournament_id, entry_fee, max_entrants, start]
+        TournamentCreated(AccountId, TournamentIndex, Balance, u32, BlockNumber),
+        // kitty 报名参加锦标赛 [owner, tournament_id, kitty_id]
+        TournamentEntered(AccountId, TournamentIndex, KittyIndex),
+        // 一场对战的结果 [tournament_id, round, winner_kitty_id, loser_kitty_id]
+        BattleFought(TournamentIndex, u32, KittyIndex, KittyIndex),
+        // 锦标赛结束, 胜者赢得奖池 [tournament_id, winner, kitty_id, pot]
+        TournamentWon(TournamentIndex, AccountId, KittyIndex, Balance),
+        // 参赛kitty不足两只, 锦标赛取消并退还报名费 [tournament_id]
+        TournamentCancelled(TournamentIndex),
 	}
 );
 
@@ -109,52 +553,626 @@ decl_module! {
 		type Error = Error<T>;
         fn deposit_event() = default;
 
+        const InbreedingDepth: u32 = T::InbreedingDepth::get();
+        const GestationPeriod: T::BlockNumber = T::GestationPeriod::get();
+        const BreedCooldown: T::BlockNumber = T::BreedCooldown::get();
+        const MutationRate: Permill = T::MutationRate::get();
+        const RevealDelay: T::BlockNumber = T::RevealDelay::get();
+        const RevealWindow: T::BlockNumber = T::RevealWindow::get();
+        const BreedBond: BalanceOf<T> = T::BreedBond::get();
+        const MetadataDepositBase: BalanceOf<T> = T::MetadataDepositBase::get();
+        const MetadataDepositPerByte: BalanceOf<T> = T::MetadataDepositPerByte::get();
+        const MaxNameLength: u32 = T::MaxNameLength::get();
+        const MaxMetadataLength: u32 = T::MaxMetadataLength::get();
+        const MaxKittiesPerAccount: u32 = T::MaxKittiesPerAccount::get();
+        const MaxBatchSize: u32 = T::MaxBatchSize::get();
+        const MaxAuctionsPerBlock: u32 = T::MaxAuctionsPerBlock::get();
+        const MaxLeasesPerBlock: u32 = T::MaxLeasesPerBlock::get();
+        const MaxTournamentSize: u32 = T::MaxTournamentSize::get();
+        const MaxTournamentsPerBlock: u32 = T::MaxTournamentsPerBlock::get();
+        const TournamentDeposit: BalanceOf<T> = T::TournamentDeposit::get();
+
+        // 迁移旧的存储布局
+        fn on_runtime_upgrade() -> Weight {
+            let mut weight = 0;
+            if Self::storage_version() == Releases::V1 {
+                weight += migrations::migrate_to_v2::<T>();
+            }
+            if Self::storage_version() == Releases::V2 {
+                weight += migrations::migrate_to_v3::<T>();
+            }
+            weight
+        }
+
+        // 怀孕到期的kitty出生, 到期的租约归还出租人, 记录揭示繁殖使用的随机数, 没收过期承诺的保证金
+        // 同时计入本区块 on_finalize 结算拍卖和进行锦标赛的权重
+        fn on_initialize(n: T::BlockNumber) -> Weight {
+            // 先于本区块的任何揭示交易记录随机数, 提交承诺时无法预知
+            if <RevealScheduled<T>>::take(n) {
+                <RevealRandomness<T>>::insert(n, T::Randomness::random(b"kitties/reveal"));
+            }
+            // 揭示期已过的随机数不再需要
+            let window = T::RevealWindow::get().saturating_add(1u32.into());
+            if n >= window {
+                <RevealRandomness<T>>::remove(n - window);
+            }
+            // 揭示期已过仍未揭示的承诺作废, 提交者看到结果后放弃揭示也要付出代价
+            let expired = <BreedCommitmentsExpiringAt<T>>::take(n);
+            let expired_count = expired.len() as Weight;
+            for who in expired {
+                if let Some((_, reveal_at, bond)) = Self::breed_commitment(&who) {
+                    if reveal_at.saturating_add(T::RevealWindow::get()) < n {
+                        <BreedCommitments<T>>::remove(&who);
+                        Self::forfeit_breed_bond(&who, bond);
+                    }
+                }
+            }
+
+            let births = <PendingBirths<T>>::take(n);
+            let count = births.len() as Weight;
+            for (owner, new_kitty, deposit) in births {
+                Self::give_birth(owner, new_kitty, deposit);
+            }
+
+            let leases = <LeasesEndingAt<T>>::take(n);
+            let lease_count = leases.len() as Weight;
+            for kitty_id in leases {
+                Self::end_lease(kitty_id);
+            }
+
+            // 拍卖结束和锦标赛进行的区块都晚于安排它们的区块, 本区块内不会再增加
+            let auction_count = <AuctionsEndingAt<T>>::decode_len(n).unwrap_or(0) as Weight;
+            let tournament_count = <TournamentsAt<T>>::decode_len(n).unwrap_or(0) as Weight;
+            // 每场锦标赛最多读取所有参赛kitty, 结束时处理所有参赛者的报名费
+            let entrants = T::MaxTournamentSize::get() as Weight;
+
+            T::DbWeight::get().reads_writes(
+                7 + expired_count * 2 + count * 4 + lease_count * 3 + auction_count * 8 + tournament_count * (3 + entrants * 3),
+                5 + expired_count * 2 + count * 6 + lease_count * 8 + auction_count * 12 + tournament_count * (4 + entrants * 3),
+            )
+        }
+
 		// 创建kitty
-		#[weight = 0]
+		#[weight = T::WeightInfo::create()]
+        #[transactional]
 		pub fn create(origin) {
             // 判断签名
 			let sender = ensure_signed(origin)?;
-			let kitty_id = Self::next_kitty_id()?;
-
-			let dna = Self::random_value(&sender);
 
-			// 创建新的kitty
-			let mut new_kitty = Kitty::new();
-            new_kitty.set_dna_data(dna);
-            Self::insert_kitty(&sender, kitty_id, new_kitty);
+			let dna = T::Genetics::create(&Self::random_value(&sender));
 
-            T::Currency::reserve(&sender, T::NewKittyReserve::get()).map_err(|_| Error::<T>::MoneyNotEnough)?;
-            Self::deposit_event(RawEvent::Created(sender, kitty_id));
+            // 先质押, 质押失败时不会创建kitty
+            let deposit = T::NewKittyReserve::get();
+            T::Currency::reserve(&sender, deposit).map_err(|_| Error::<T>::MoneyNotEnough)?;
+            Self::mint_kitty(&sender, dna, deposit)?;
 		}
-        #[weight = 0]
+        // 一次创建 count 只kitty, 一次性质押 NewKittyReserve * count, 任何一只创建失败则全部回滚
+        #[weight = T::WeightInfo::create_batch(*count)]
+        #[transactional]
+        pub fn create_batch(origin, count: u32) {
+            let sender = ensure_signed(origin)?;
+            ensure!(count <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
+
+            let deposit = T::NewKittyReserve::get();
+            T::Currency::reserve(&sender, deposit.saturating_mul(count.into()))
+                .map_err(|_| Error::<T>::MoneyNotEnough)?;
+
+            // 同一交易中随机种子相同, 用序号区分每只kitty的dna
+            let seed = Self::random_value(&sender);
+            for i in 0..count {
+                let dna = T::Genetics::create(&(seed, i).using_encoded(blake2_128));
+                Self::mint_kitty(&sender, dna, deposit)?;
+            }
+        }
+        #[weight = T::WeightInfo::transfer()]
         pub fn transfer(origin, to: T::AccountId, kitty_id: T::KittyIndex) {
             let sender = ensure_signed(origin)?;
             // 视频错误 没有校验kitty的所有者
             // 修正
-            let account_id = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
-            ensure!(account_id == sender.clone(),Error::<T>::NotKittyOwner);
-
-            <KittyOwner<T>>::insert(kitty_id, to.clone());
-            // 移除原来所有者的记录
-            OwnedKitties::<T>::mutate(&sender, |val| val.retain(|&temp| temp != kitty_id));
-            // 记录新的所有者的记录
-            OwnedKitties::<T>::mutate(&to, |val| val.push(kitty_id));
-            
+            Self::ensure_kitty_owner(&sender, kitty_id)?;
+            Self::ensure_kitty_unlocked(kitty_id)?;
+
+            Self::do_transfer(&sender, &to, kitty_id)?;
+
             Self::deposit_event(RawEvent::Transfered(sender, to, kitty_id));
+        }
+        // 一次转移多只kitty, 任何一只转移失败则全部回滚
+        #[weight = T::WeightInfo::transfer_batch(transfers.len() as u32)]
+        #[transactional]
+        pub fn transfer_batch(origin, transfers: vec::Vec<(T::AccountId, T::KittyIndex)>) {
+            let sender = ensure_signed(origin)?;
+            ensure!(transfers.len() as u32 <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
+
+            for (to, kitty_id) in transfers {
+                Self::ensure_kitty_owner(&sender, kitty_id)?;
+                Self::ensure_kitty_unlocked(kitty_id)?;
 
+                Self::do_transfer(&sender, &to, kitty_id)?;
 
+                Self::deposit_event(RawEvent::Transfered(sender.clone(), to, kitty_id));
+            }
+        }
+        // 授权 approved 转移kitty, None 表示撤销授权, 所有者和操作员可以调用
+        #[weight = T::WeightInfo::approve()]
+        pub fn approve(origin, kitty_id: T::KittyIndex, approved: Option<T::AccountId>) {
+            let sender = ensure_signed(origin)?;
+            let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+            ensure!(owner == sender || Self::is_approved_for_all(&owner, &sender), Error::<T>::NotApproved);
+
+            match &approved {
+                Some(account) => <KittyApprovals<T>>::insert(kitty_id, account),
+                None => <KittyApprovals<T>>::remove(kitty_id),
+            }
+
+            Self::deposit_event(RawEvent::Approval(owner, kitty_id, approved));
+        }
+        // 授权或撤销操作员转移自己所有的kitty
+        #[weight = T::WeightInfo::set_approval_for_all()]
+        pub fn set_approval_for_all(origin, operator: T::AccountId, approved: bool) {
+            let sender = ensure_signed(origin)?;
+
+            if approved {
+                <OperatorApprovals<T>>::insert(&sender, &operator, true);
+            } else {
+                <OperatorApprovals<T>>::remove(&sender, &operator);
+            }
+
+            Self::deposit_event(RawEvent::ApprovalForAll(sender, operator, approved));
+        }
+        // 由所有者, 被授权人或操作员把kitty从 from 转移给 to
+        #[weight = T::WeightInfo::transfer_from()]
+        pub fn transfer_from(origin, from: T::AccountId, to: T::AccountId, kitty_id: T::KittyIndex) {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_kitty_owner(&from, kitty_id)?;
+            ensure!(
+                sender == from ||
+                    Self::kitty_approval(kitty_id).as_ref() == Some(&sender) ||
+                    Self::is_approved_for_all(&from, &sender),
+                Error::<T>::NotApproved
+            );
+            Self::ensure_kitty_unlocked(kitty_id)?;
+
+            Self::do_transfer(&from, &to, kitty_id)?;
+
+            Self::deposit_event(RawEvent::Transfered(from, to, kitty_id));
+        }
+        // 设置名字和元数据, 按字节质押, 覆盖时退还之前的质押
+        #[weight = T::WeightInfo::set_metadata((name.len() + data.len()) as u32)]
+        pub fn set_metadata(origin, kitty_id: T::KittyIndex, name: vec::Vec<u8>, data: vec::Vec<u8>) {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_kitty_owner(&sender, kitty_id)?;
+            // 承租人只能使用和展示kitty, 不能修改出租人设置的名字和元数据
+            ensure!(!<Leases<T>>::contains_key(kitty_id), Error::<T>::KittyLeased);
+            ensure!(name.len() as u32 <= T::MaxNameLength::get(), Error::<T>::NameTooLong);
+            ensure!(data.len() as u32 <= T::MaxMetadataLength::get(), Error::<T>::MetadataTooLong);
+
+            let bytes = (name.len() + data.len()) as u32;
+            let deposit = T::MetadataDepositBase::get()
+                .saturating_add(T::MetadataDepositPerByte::get().saturating_mul(bytes.into()));
+            T::Currency::reserve(&sender, deposit).map_err(|_| Error::<T>::MoneyNotEnough)?;
+
+            let metadata = KittyMetadata { name, data, depositor: sender.clone(), deposit };
+            if let Some(old) = <Metadata<T>>::mutate(kitty_id, |val| val.replace(metadata)) {
+                T::Currency::unreserve(&old.depositor, old.deposit);
+            }
+
+            Self::deposit_event(RawEvent::MetadataSet(sender, kitty_id));
+        }
+        // 清除名字和元数据, 退还质押
+        #[weight = T::WeightInfo::clear_metadata()]
+        pub fn clear_metadata(origin, kitty_id: T::KittyIndex) {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_kitty_owner(&sender, kitty_id)?;
+            ensure!(!<Leases<T>>::contains_key(kitty_id), Error::<T>::KittyLeased);
+
+            let old = <Metadata<T>>::take(kitty_id).ok_or(Error::<T>::MetadataNotFound)?;
+            T::Currency::unreserve(&old.depositor, old.deposit);
+
+            Self::deposit_event(RawEvent::MetadataCleared(sender, kitty_id));
+        }
+        // 销毁kitty, 并退还创建时的质押给质押人
+        #[weight = T::WeightInfo::release()]
+        pub fn release(origin, kitty_id: T::KittyIndex) {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_kitty_owner(&sender, kitty_id)?;
+            Self::ensure_kitty_unlocked(kitty_id)?;
+
+            if let Some((depositor, deposit)) = Self::remove_kitty(&sender, kitty_id) {
+                T::Currency::unreserve(&depositor, deposit);
+            }
+
+            Self::deposit_event(RawEvent::Released(sender, kitty_id));
+        }
+        // 销毁自己的两只kitty, 融合出一只新的kitty, 保留其中一份质押, 退还另一份
+        #[weight = T::WeightInfo::fuse()]
+        #[transactional]
+        pub fn fuse(origin, kitty_id_a: T::KittyIndex, kitty_id_b: T::KittyIndex) {
+            let sender = ensure_signed(origin)?;
+            ensure!(kitty_id_a != kitty_id_b, Error::<T>::RequireDifferentParent);
+            Self::ensure_kitty_owner(&sender, kitty_id_a)?;
+            Self::ensure_kitty_owner(&sender, kitty_id_b)?;
+            Self::ensure_kitty_unlocked(kitty_id_a)?;
+            Self::ensure_kitty_unlocked(kitty_id_b)?;
+            let kitty_a = Self::kitties(kitty_id_a).ok_or(Error::<T>::InvalidKittyId)?;
+            let kitty_b = Self::kitties(kitty_id_b).ok_or(Error::<T>::InvalidKittyId)?;
+            let new_kitty_id = Self::next_kitty_id()?;
+
+            // 与繁殖相同, 由两只kitty的dna和随机值组合出新的dna
+            let selector = Self::random_value(&sender);
+            let mut new_kitty = Kitty::new();
+            new_kitty.set_kitty_id(new_kitty_id);
+            new_kitty.set_dna_data(T::Genetics::recombine(&kitty_a.dna_data, &kitty_b.dna_data, &selector));
+            new_kitty.set_parents_ids(Some(kitty_id_a), Some(kitty_id_b));
+            new_kitty.set_generation(kitty_a.generation.max(kitty_b.generation).saturating_add(1));
+
+            // 新kitty 沿用其中一份质押, 创世kitty没有质押
+            let (retained, released) = match (
+                Self::remove_kitty(&sender, kitty_id_a),
+                Self::remove_kitty(&sender, kitty_id_b),
+            ) {
+                (Some(deposit_a), deposit_b) => (Some(deposit_a), deposit_b),
+                (None, deposit_b) => (deposit_b, None),
+            };
+            if let Some((depositor, deposit)) = released {
+                T::Currency::unreserve(&depositor, deposit);
+            }
+
+            let (generation, rarity) = (new_kitty.generation(), new_kitty.rarity());
+            Self::insert_kitty(&sender, new_kitty_id, new_kitty)?;
+            if let Some(deposit) = retained {
+                <KittyDeposits<T>>::insert(new_kitty_id, deposit);
+            }
+            <FusedKitties<T>>::insert(new_kitty_id, true);
+
+            Self::deposit_event(RawEvent::Created(sender.clone(), new_kitty_id, generation, rarity));
+            Self::deposit_event(RawEvent::Fused(sender, kitty_id_a, kitty_id_b, new_kitty_id));
+        }
+        // 设置一口价, None 表示撤销挂单(包括荷兰式拍卖)
+        #[weight = T::WeightInfo::set_price()]
+        pub fn set_price(origin, kitty_id: T::KittyIndex, new_price: Option<BalanceOf<T>>) {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_kitty_owner(&sender, kitty_id)?;
+            Self::ensure_kitty_unlocked(kitty_id)?;
+
+            match new_price {
+                Some(price) => <KittyPrices<T>>::insert(kitty_id, price),
+                None => <KittyPrices<T>>::remove(kitty_id),
+            }
+            <DutchAuctions<T>>::remove(kitty_id);
+
+            Self::deposit_event(RawEvent::PriceSet(sender, kitty_id, new_price));
         }
-		#[weight = 0]
+        // 创建荷兰式拍卖, 第一个出价不低于当前价格的买家通过 buy 成交
+        #[weight = T::WeightInfo::create_dutch_auction()]
+        pub fn create_dutch_auction(
+            origin,
+            kitty_id: T::KittyIndex,
+            start_price: BalanceOf<T>,
+            floor_price: BalanceOf<T>,
+            duration: T::BlockNumber
+        ) {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_kitty_owner(&sender, kitty_id)?;
+            Self::ensure_kitty_unlocked(kitty_id)?;
+            ensure!(!duration.is_zero(), Error::<T>::InvalidAuctionDuration);
+            ensure!(start_price >= floor_price, Error::<T>::InvalidPriceRange);
+
+            let auction = DutchAuction {
+                start_price,
+                floor_price,
+                start: <frame_system::Module<T>>::block_number(),
+                duration,
+            };
+            <DutchAuctions<T>>::insert(kitty_id, auction);
+            <KittyPrices<T>>::remove(kitty_id);
+
+            Self::deposit_event(RawEvent::DutchAuctionCreated(sender, kitty_id, start_price, floor_price, duration));
+        }
+        // 按挂单价格购买kitty, max_price 防止卖家在交易打包前抬价
+        #[weight = T::WeightInfo::buy()]
+        #[transactional]
+        pub fn buy(origin, kitty_id: T::KittyIndex, max_price: BalanceOf<T>) {
+            let sender = ensure_signed(origin)?;
+            let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+            ensure!(owner != sender, Error::<T>::BuyOwnKitty);
+
+            let price = Self::current_price(kitty_id).ok_or(Error::<T>::NotForSale)?;
+            ensure!(price <= max_price, Error::<T>::PriceTooHigh);
+
+            T::Currency::transfer(&sender, &owner, price, ExistenceRequirement::KeepAlive)
+                .map_err(|_| Error::<T>::MoneyNotEnough)?;
+            Self::do_transfer(&owner, &sender, kitty_id)?;
+
+            Self::deposit_event(RawEvent::Sold(sender, owner, kitty_id, price));
+        }
+        // 创建英式拍卖, 拍卖持续 duration 个区块
+        #[weight = T::WeightInfo::create_auction()]
+        pub fn create_auction(origin, kitty_id: T::KittyIndex, min_bid: BalanceOf<T>, duration: T::BlockNumber) {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_kitty_owner(&sender, kitty_id)?;
+            Self::ensure_kitty_unlocked(kitty_id)?;
+            ensure!(!duration.is_zero(), Error::<T>::InvalidAuctionDuration);
+
+            let auction_id = Self::auctions_count();
+            let next_auction_id = auction_id.checked_add(1).ok_or(Error::<T>::AuctionsCountOverflow)?;
+            let end = <frame_system::Module<T>>::block_number().saturating_add(duration);
+            let ending = <AuctionsEndingAt<T>>::decode_len(end).unwrap_or(0) as u32;
+            ensure!(ending < T::MaxAuctionsPerBlock::get(), Error::<T>::TooManyAuctionsEnding);
+
+            let auction = Auction {
+                kitty_id,
+                seller: sender.clone(),
+                min_bid,
+                best_bid: None,
+                end,
+            };
+            <Auctions<T>>::insert(auction_id, auction);
+            <AuctionsCount>::put(next_auction_id);
+            <AuctionsEndingAt<T>>::mutate(end, |val| val.push(auction_id));
+            <KittyAuction<T>>::insert(kitty_id, auction_id);
+            // 拍卖和一口价互斥
+            <KittyPrices<T>>::remove(kitty_id);
+            <DutchAuctions<T>>::remove(kitty_id);
+
+            Self::deposit_event(RawEvent::AuctionCreated(sender, auction_id, kitty_id, min_bid, end));
+        }
+        // 出价, 出价金额会被质押, 被超过时退还
+        #[weight = T::WeightInfo::bid()]
+        pub fn bid(origin, auction_id: AuctionIndex, amount: BalanceOf<T>) {
+            let sender = ensure_signed(origin)?;
+            let mut auction = Self::auctions(auction_id).ok_or(Error::<T>::InvalidAuctionId)?;
+            ensure!(<frame_system::Module<T>>::block_number() <= auction.end, Error::<T>::AuctionEnded);
+            ensure!(auction.seller != sender, Error::<T>::BidOwnAuction);
+            ensure!(amount >= auction.min_bid, Error::<T>::BidTooLow);
+            if let Some((_, best)) = &auction.best_bid {
+                ensure!(amount > *best, Error::<T>::BidTooLow);
+            }
+
+            T::Currency::reserve(&sender, amount).map_err(|_| Error::<T>::MoneyNotEnough)?;
+            // 退还上一个出价人的质押
+            if let Some((bidder, best)) = auction.best_bid.take() {
+                T::Currency::unreserve(&bidder, best);
+            }
+            auction.best_bid = Some((sender.clone(), amount));
+            <Auctions<T>>::insert(auction_id, auction);
+
+            Self::deposit_event(RawEvent::BidPlaced(sender, auction_id, amount));
+        }
+        // 创建锦标赛并质押 TournamentDeposit, 报名在 registration_period 个区块后截止, 之后每个区块进行一轮
+        #[weight = T::WeightInfo::create_tournament()]
+        pub fn create_tournament(origin, entry_fee: BalanceOf<T>, max_entrants: u32, registration_period: T::BlockNumber) {
+            let sender = ensure_signed(origin)?;
+            ensure!(max_entrants >= 2 && max_entrants <= T::MaxTournamentSize::get(), Error::<T>::InvalidTournamentSize);
+            ensure!(!registration_period.is_zero(), Error::<T>::InvalidTournamentStart);
+
+            let tournament_id = Self::tournaments_count();
+            let next_tournament_id = tournament_id.checked_add(1).ok_or(Error::<T>::TournamentsCountOverflow)?;
+            let start = <frame_system::Module<T>>::block_number().saturating_add(registration_period);
+            let starting = <TournamentsAt<T>>::decode_len(start).unwrap_or(0) as u32;
+            ensure!(starting < T::MaxTournamentsPerBlock::get(), Error::<T>::TooManyTournaments);
+
+            let deposit = T::TournamentDeposit::get();
+            T::Currency::reserve(&sender, deposit).map_err(|_| Error::<T>::MoneyNotEnough)?;
+
+            let tournament = Tournament {
+                creator: sender.clone(),
+                deposit,
+                entry_fee,
+                max_entrants,
+                start,
+                entrants: vec::Vec::new(),
+                remaining: vec::Vec::new(),
+                round: 0,
+            };
+            <Tournaments<T>>::insert(tournament_id, tournament);
+            <TournamentsCount>::put(next_tournament_id);
+            <TournamentsAt<T>>::mutate(start, |val| val.push(tournament_id));
+
+            Self::deposit_event(RawEvent::TournamentCreated(sender, tournament_id, entry_fee, max_entrants, start));
+        }
+        // 用自己的kitty报名参加锦标赛, 报名费会被质押, 锦标赛结束前kitty不能转移和繁殖
+        #[weight = T::WeightInfo::enter_tournament()]
+        pub fn enter_tournament(origin, tournament_id: TournamentIndex, kitty_id: T::KittyIndex) {
+            let sender = ensure_signed(origin)?;
+            let mut tournament = Self::tournaments(tournament_id).ok_or(Error::<T>::InvalidTournamentId)?;
+            Self::ensure_kitty_owner(&sender, kitty_id)?;
+            Self::ensure_kitty_unlocked(kitty_id)?;
+            ensure!(<frame_system::Module<T>>::block_number() < tournament.start, Error::<T>::RegistrationClosed);
+            ensure!((tournament.entrants.len() as u32) < tournament.max_entrants, Error::<T>::TournamentFull);
+
+            T::Currency::reserve(&sender, tournament.entry_fee).map_err(|_| Error::<T>::MoneyNotEnough)?;
+            tournament.entrants.push((sender.clone(), kitty_id));
+            <Tournaments<T>>::insert(tournament_id, tournament);
+            <KittyTournament<T>>::insert(kitty_id, tournament_id);
+            // 参赛期间不能出售
+            <KittyPrices<T>>::remove(kitty_id);
+            <DutchAuctions<T>>::remove(kitty_id);
+
+            Self::deposit_event(RawEvent::TournamentEntered(sender, tournament_id, kitty_id));
+        }
+
+        // 结算到期的拍卖, 进行锦标赛的下一轮
+        fn on_finalize(n: T::BlockNumber) {
+            for auction_id in <AuctionsEndingAt<T>>::take(n) {
+                Self::settle_auction(auction_id);
+            }
+            for tournament_id in <TournamentsAt<T>>::take(n) {
+                Self::run_tournament_round(tournament_id, n);
+            }
+        }
+		#[weight = T::WeightInfo::breed(Module::<T>::max_ancestors())]
 		pub fn breed(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) {
 			let sender = ensure_signed(origin)?;
-			let new_kitty_id = Self::do_breed(sender.clone(), kitty_id_1, kitty_id_2)?;
-            Self::deposit_event(RawEvent::Created(sender, new_kitty_id));
+            ensure!(Self::instant_breed_allowed(), Error::<T>::InstantBreedDisabled);
+            // 只能用自己的kitty繁殖, 别人的kitty需要通过 breed_with 付费配种
+            Self::ensure_kitty_owner(&sender, kitty_id_1)?;
+            Self::ensure_kitty_owner(&sender, kitty_id_2)?;
+
+			let due = Self::do_breed(sender.clone(), kitty_id_1, kitty_id_2, Self::random_value(&sender), Self::mutation_seed(&sender))?;
+            Self::deposit_event(RawEvent::BreedingStarted(sender, kitty_id_1, kitty_id_2, due));
 		}
+        // 开启或关闭一步繁殖, 只能由 root 调用
+        #[weight = T::WeightInfo::set_instant_breed_allowed()]
+        pub fn set_instant_breed_allowed(origin, allowed: bool) {
+            ensure_root(origin)?;
+
+            InstantBreedAllowed::put(allowed);
+
+            Self::deposit_event(RawEvent::InstantBreedAllowedSet(allowed));
+        }
+        // 提交繁殖承诺 commitment = Hashing(kitty_id_1, kitty_id_2, salt) 并质押 BreedBond
+        // 覆盖之前未揭示的承诺时没收之前的保证金, 重新提交不能免费重新抽取dna
+        #[weight = T::WeightInfo::commit_breed()]
+        pub fn commit_breed(origin, commitment: T::Hash) {
+            let sender = ensure_signed(origin)?;
+
+            let bond = T::BreedBond::get();
+            T::Currency::reserve(&sender, bond).map_err(|_| Error::<T>::MoneyNotEnough)?;
+            if let Some((_, _, old_bond)) = Self::breed_commitment(&sender) {
+                Self::forfeit_breed_bond(&sender, old_bond);
+            }
+
+            let reveal_at = <frame_system::Module<T>>::block_number()
+                .saturating_add(T::RevealDelay::get().max(1u32.into()));
+            <BreedCommitments<T>>::insert(&sender, (commitment, reveal_at, bond));
+            <RevealScheduled<T>>::insert(reveal_at, true);
+            let expire_at = reveal_at.saturating_add(T::RevealWindow::get()).saturating_add(1u32.into());
+            <BreedCommitmentsExpiringAt<T>>::mutate(expire_at, |val| val.push(sender.clone()));
+
+            Self::deposit_event(RawEvent::BreedCommitted(sender, commitment, reveal_at));
+        }
+        // 揭示繁殖承诺并繁殖, 从揭示区块开始的 RevealWindow 个区块内有效, 成功后退还保证金
+        // dna 由盐和揭示区块记录的随机数共同决定, 与揭示交易在哪个区块上链无关
+        // kitty_id_2 属于别人时需要有配种服务, 并支付配种费用, max_fee 防止配种服务提供者在交易打包前抬价
+        #[weight = T::WeightInfo::reveal_breed(Module::<T>::max_ancestors())]
+        #[transactional]
+        pub fn reveal_breed(origin, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex, salt: [u8; 32], max_fee: BalanceOf<T>) {
+            let sender = ensure_signed(origin)?;
+            let (commitment, reveal_at, bond) = Self::breed_commitment(&sender).ok_or(Error::<T>::NoBreedCommitment)?;
+            ensure!(commitment == Self::breed_commitment_hash(kitty_id_1, kitty_id_2, &salt), Error::<T>::InvalidReveal);
+            let now = <frame_system::Module<T>>::block_number();
+            ensure!(now >= reveal_at, Error::<T>::RevealTooEarly);
+            ensure!(now <= reveal_at.saturating_add(T::RevealWindow::get()), Error::<T>::CommitmentExpired);
+            Self::ensure_kitty_owner(&sender, kitty_id_1)?;
+            let sire_owner = Self::kitty_owner(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
+            let fee = if sire_owner == sender {
+                None
+            } else {
+                let fee = Self::siring_offer(kitty_id_2).ok_or(Error::<T>::NoSiringOffer)?;
+                ensure!(fee <= max_fee, Error::<T>::PriceTooHigh);
+                Some(fee)
+            };
+
+            <BreedCommitments<T>>::remove(&sender);
+            T::Currency::unreserve(&sender, bond);
+            let (selector, mutation_seed) = Self::revealed_seeds(&sender, reveal_at, &salt)
+                .ok_or(Error::<T>::CommitmentExpired)?;
+            let due = Self::do_breed(sender.clone(), kitty_id_1, kitty_id_2, selector, mutation_seed)?;
+            if let Some(fee) = fee {
+                T::Currency::transfer(&sender, &sire_owner, fee, ExistenceRequirement::KeepAlive)
+                    .map_err(|_| Error::<T>::MoneyNotEnough)?;
+                Self::deposit_event(RawEvent::SiringFeePaid(sender.clone(), sire_owner, kitty_id_2, fee));
+            }
+
+            Self::deposit_event(RawEvent::BreedingStarted(sender, kitty_id_1, kitty_id_2, due));
+        }
+        // 提供配种服务, 其他人支付 fee 后可以用该kitty繁殖
+        #[weight = T::WeightInfo::offer_siring()]
+        pub fn offer_siring(origin, kitty_id: T::KittyIndex, fee: BalanceOf<T>) {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_kitty_owner(&sender, kitty_id)?;
+            Self::ensure_kitty_unlocked(kitty_id)?;
+
+            <SiringOffers<T>>::insert(kitty_id, fee);
+
+            Self::deposit_event(RawEvent::SiringOffered(sender, kitty_id, Some(fee)));
+        }
+        // 撤销配种服务
+        #[weight = T::WeightInfo::cancel_siring_offer()]
+        pub fn cancel_siring_offer(origin, kitty_id: T::KittyIndex) {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_kitty_owner(&sender, kitty_id)?;
+
+            <SiringOffers<T>>::remove(kitty_id);
+
+            Self::deposit_event(RawEvent::SiringOffered(sender, kitty_id, None));
+        }
+        // 支付配种费用, 用自己的kitty和别人提供配种服务的kitty繁殖, 孩子属于调用者
+        // max_fee 防止配种服务提供者在交易打包前抬价
+        #[weight = T::WeightInfo::breed_with(Module::<T>::max_ancestors())]
+        #[transactional]
+        pub fn breed_with(origin, my_kitty_id: T::KittyIndex, sire_id: T::KittyIndex, max_fee: BalanceOf<T>) {
+            let sender = ensure_signed(origin)?;
+            ensure!(Self::instant_breed_allowed(), Error::<T>::InstantBreedDisabled);
+            Self::ensure_kitty_owner(&sender, my_kitty_id)?;
+            let sire_owner = Self::kitty_owner(sire_id).ok_or(Error::<T>::InvalidKittyId)?;
+            let fee = Self::siring_offer(sire_id).ok_or(Error::<T>::NoSiringOffer)?;
+            ensure!(fee <= max_fee, Error::<T>::PriceTooHigh);
+
+            let due = Self::do_breed(sender.clone(), my_kitty_id, sire_id, Self::random_value(&sender), Self::mutation_seed(&sender))?;
+            T::Currency::transfer(&sender, &sire_owner, fee, ExistenceRequirement::KeepAlive)
+                .map_err(|_| Error::<T>::MoneyNotEnough)?;
+
+            Self::deposit_event(RawEvent::SiringFeePaid(sender.clone(), sire_owner, sire_id, fee));
+            Self::deposit_event(RawEvent::BreedingStarted(sender, my_kitty_id, sire_id, due));
+        }
+        // 向 lessee 提供出租邀约, 租期 duration 个区块, 租金 price
+        #[weight = T::WeightInfo::lease()]
+        pub fn lease(origin, kitty_id: T::KittyIndex, lessee: T::AccountId, duration: T::BlockNumber, price: BalanceOf<T>) {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_kitty_owner(&sender, kitty_id)?;
+            Self::ensure_kitty_unlocked(kitty_id)?;
+            ensure!(lessee != sender, Error::<T>::LeaseOwnKitty);
+            ensure!(!duration.is_zero(), Error::<T>::InvalidLeaseDuration);
+
+            <LeaseOffers<T>>::insert(kitty_id, LeaseOffer { lessee: lessee.clone(), duration, price });
+
+            Self::deposit_event(RawEvent::LeaseOffered(sender, kitty_id, Some((lessee, duration, price))));
+        }
+        // 撤销出租邀约
+        #[weight = T::WeightInfo::cancel_lease_offer()]
+        pub fn cancel_lease_offer(origin, kitty_id: T::KittyIndex) {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_kitty_owner(&sender, kitty_id)?;
+
+            <LeaseOffers<T>>::remove(kitty_id);
+
+            Self::deposit_event(RawEvent::LeaseOffered(sender, kitty_id, None));
+        }
+        // 承租人接受出租邀约并支付租金, max_price 防止出租人在交易打包前抬价
+        #[weight = T::WeightInfo::accept_lease()]
+        #[transactional]
+        pub fn accept_lease(origin, kitty_id: T::KittyIndex, max_price: BalanceOf<T>) {
+            let sender = ensure_signed(origin)?;
+            let lessor = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+            let offer = Self::lease_offer(kitty_id).ok_or(Error::<T>::NoLeaseOffer)?;
+            ensure!(offer.lessee == sender, Error::<T>::NotLessee);
+            ensure!(offer.price <= max_price, Error::<T>::PriceTooHigh);
+            Self::ensure_kitty_unlocked(kitty_id)?;
+            Self::ensure_can_own(&sender)?;
+            let end = <frame_system::Module<T>>::block_number().saturating_add(offer.duration);
+            let ending = <LeasesEndingAt<T>>::decode_len(end).unwrap_or(0) as u32;
+            ensure!(ending < T::MaxLeasesPerBlock::get(), Error::<T>::TooManyLeasesEnding);
+
+            T::Currency::transfer(&sender, &lessor, offer.price, ExistenceRequirement::KeepAlive)
+                .map_err(|_| Error::<T>::MoneyNotEnough)?;
+
+            // 租期内所有者为承租人, 质押仍由原质押人承担
+            <KittyOwner<T>>::insert(kitty_id, &sender);
+            Self::remove_owned_kitty(&lessor, kitty_id);
+            Self::add_owned_kitty(&sender, kitty_id);
+            <KittyPrices<T>>::remove(kitty_id);
+            <DutchAuctions<T>>::remove(kitty_id);
+            <KittyApprovals<T>>::remove(kitty_id);
+            <SiringOffers<T>>::remove(kitty_id);
+            <LeaseOffers<T>>::remove(kitty_id);
+            <Leases<T>>::insert(kitty_id, Lease { lessor: lessor.clone(), lessee: sender.clone(), end });
+            <LeasesEndingAt<T>>::mutate(end, |val| val.push(kitty_id));
+
+            Self::deposit_event(RawEvent::LeaseStarted(lessor, sender, kitty_id, offer.price, end));
+        }
 	}
 }
-fn combine_dna(dna1: u8, dna2: u8, selector: u8) -> u8 {
-    (selector & dna1) | (!selector & dna2)
-}
 impl<T: Trait> Module<T> {
     
     fn next_kitty_id() -> sp_std::result::Result<T::KittyIndex, DispatchError> {
@@ -174,21 +1192,390 @@ impl<T: Trait> Module<T> {
         payload.using_encoded(blake2_128)
 	}
 
-	fn insert_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex, kitty: Kitty<T::KittyIndex>) {
+    // 校验kitty存在且属于 who
+    fn ensure_kitty_owner(who: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+        let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+        ensure!(owner == *who, Error::<T>::NotKittyOwner);
+        Ok(())
+    }
+
+    // 校验账号拥有的kitty数量没有达到上限
+    fn ensure_can_own(who: &T::AccountId) -> DispatchResult {
+        ensure!(Self::owned_kitties_count(who) < T::MaxKittiesPerAccount::get(), Error::<T>::TooManyKitties);
+        Ok(())
+    }
+
+    // 校验kitty可以繁殖
+    fn ensure_kitty_can_breed(kitty_id: T::KittyIndex) -> DispatchResult {
+        ensure!(!<KittyAuction<T>>::contains_key(kitty_id), Error::<T>::KittyLocked);
+        ensure!(!<KittyTournament<T>>::contains_key(kitty_id), Error::<T>::KittyLocked);
+        Ok(())
+    }
+
+    // 校验kitty没有被锁定, 出租中的kitty可以繁殖但不能转移, 出售和销毁
+    fn ensure_kitty_unlocked(kitty_id: T::KittyIndex) -> DispatchResult {
+        Self::ensure_kitty_can_breed(kitty_id)?;
+        ensure!(!<Leases<T>>::contains_key(kitty_id), Error::<T>::KittyLeased);
+        Ok(())
+    }
+
+    // 结算拍卖: 有人出价则成交, 否则流拍
+    fn settle_auction(auction_id: AuctionIndex) {
+        let auction = match <Auctions<T>>::take(auction_id) {
+            Some(auction) => auction,
+            None => return,
+        };
+        <KittyAuction<T>>::remove(auction.kitty_id);
+
+        match auction.best_bid {
+            Some((winner, amount)) => {
+                // 中标人无法承担kitty的质押时流拍, 退还出价
+                if Self::do_transfer(&auction.seller, &winner, auction.kitty_id).is_err() {
+                    T::Currency::unreserve(&winner, amount);
+                    Self::deposit_event(RawEvent::AuctionCancelled(auction_id, auction.kitty_id));
+                    return;
+                }
+                // 将中标人质押的出价转给卖家
+                let _ = T::Currency::repatriate_reserved(&winner, &auction.seller, amount, BalanceStatus::Free);
+                Self::deposit_event(RawEvent::AuctionSettled(auction_id, auction.kitty_id, winner, amount));
+            }
+            None => {
+                Self::deposit_event(RawEvent::AuctionCancelled(auction_id, auction.kitty_id));
+            }
+        }
+    }
+
+    // 进行锦标赛的一轮: 剩余的参赛者按顺序两两对战, 落单的直接晋级
+    // 只剩一只kitty时锦标赛结束, 否则在下一个区块继续
+    fn run_tournament_round(tournament_id: TournamentIndex, n: T::BlockNumber) {
+        let mut tournament = match <Tournaments<T>>::get(tournament_id) {
+            Some(tournament) => tournament,
+            None => return,
+        };
+        if tournament.round == 0 {
+            if tournament.entrants.len() < 2 {
+                Self::cancel_tournament(tournament_id, tournament);
+                return;
+            }
+            tournament.remaining = (0..tournament.entrants.len() as u32).collect();
+        }
+
+        // 报名截止后才取随机数, 报名时无法预知对战结果
+        let seed = T::Randomness::random(&(b"kitties/tournament", tournament_id, tournament.round).encode());
+        let mut survivors = vec::Vec::new();
+        for (i, pair) in tournament.remaining.chunks(2).enumerate() {
+            match *pair {
+                [a, b] => {
+                    let kitty_a = tournament.entrants[a as usize].1;
+                    let kitty_b = tournament.entrants[b as usize].1;
+                    let roll = (&seed, i as u32).using_encoded(blake2_128);
+                    let roll = u32::from_le_bytes([roll[0], roll[1], roll[2], roll[3]]);
+                    let (winner, loser) = if stats::battle(&Self::stats_of(kitty_a), &Self::stats_of(kitty_b), roll) {
+                        (a, b)
+                    } else {
+                        (b, a)
+                    };
+                    survivors.push(winner);
+                    Self::deposit_event(RawEvent::BattleFought(
+                        tournament_id,
+                        tournament.round,
+                        tournament.entrants[winner as usize].1,
+                        tournament.entrants[loser as usize].1,
+                    ));
+                }
+                [a] => survivors.push(a),
+                _ => {}
+            }
+        }
+        tournament.round += 1;
+
+        if survivors.len() == 1 {
+            Self::finish_tournament(tournament_id, tournament, survivors[0]);
+        } else {
+            tournament.remaining = survivors;
+            <Tournaments<T>>::insert(tournament_id, tournament);
+            Self::schedule_tournament_round(tournament_id, n.saturating_add(1u32.into()));
+        }
+    }
+
+    // 下一轮安排在 at 之后第一个还有空位的区块
+    fn schedule_tournament_round(tournament_id: TournamentIndex, mut at: T::BlockNumber) {
+        while <TournamentsAt<T>>::decode_len(at).unwrap_or(0) as u32 >= T::MaxTournamentsPerBlock::get().max(1) {
+            at = at.saturating_add(1u32.into());
+        }
+        <TournamentsAt<T>>::mutate(at, |val| val.push(tournament_id));
+    }
+
+    // 锦标赛结束: 把其他参赛者质押的报名费转给胜者, 解锁所有参赛的kitty, 退还创建者的质押
+    fn finish_tournament(tournament_id: TournamentIndex, tournament: TournamentOf<T>, winner: u32) {
+        <Tournaments<T>>::remove(tournament_id);
+        T::Currency::unreserve(&tournament.creator, tournament.deposit);
+        let (winner, winner_kitty) = tournament.entrants[winner as usize].clone();
+        let mut pot = BalanceOf::<T>::zero();
+        for (owner, kitty_id) in tournament.entrants.iter() {
+            <KittyTournament<T>>::remove(kitty_id);
+            if *owner == winner {
+                T::Currency::unreserve(owner, tournament.entry_fee);
+                pot = pot.saturating_add(tournament.entry_fee);
+            } else if let Ok(missing) = T::Currency::repatriate_reserved(owner, &winner, tournament.entry_fee, BalanceStatus::Free) {
+                pot = pot.saturating_add(tournament.entry_fee.saturating_sub(missing));
+            }
+        }
+
+        Self::deposit_event(RawEvent::TournamentWon(tournament_id, winner, winner_kitty, pot));
+    }
+
+    // 取消锦标赛, 退还报名费和创建者的质押并解锁参赛的kitty
+    fn cancel_tournament(tournament_id: TournamentIndex, tournament: TournamentOf<T>) {
+        <Tournaments<T>>::remove(tournament_id);
+        T::Currency::unreserve(&tournament.creator, tournament.deposit);
+        for (owner, kitty_id) in tournament.entrants.iter() {
+            <KittyTournament<T>>::remove(kitty_id);
+            T::Currency::unreserve(owner, tournament.entry_fee);
+        }
+
+        Self::deposit_event(RawEvent::TournamentCancelled(tournament_id));
+    }
+
+    // 转移kitty所有权, 同时清除挂单, 授权和配种服务
+    // 在 OwnerPays 模式下质押随之转移, 新所有者无法承担质押或拥有的kitty数量已达上限时失败且不修改任何存储
+    fn do_transfer(from: &T::AccountId, to: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+        if from != to {
+            Self::ensure_can_own(to)?;
+        }
+        if T::DepositPolicy::get() == DepositPolicy::OwnerPays {
+            if let Some((depositor, deposit)) = Self::kitty_deposit(kitty_id) {
+                if depositor != *to {
+                    T::Currency::reserve(to, deposit).map_err(|_| Error::<T>::MoneyNotEnough)?;
+                    T::Currency::unreserve(&depositor, deposit);
+                    <KittyDeposits<T>>::insert(kitty_id, (to.clone(), deposit));
+                }
+            }
+        }
+
+        <KittyOwner<T>>::insert(kitty_id, to);
+        // 移除原来所有者的记录
+        Self::remove_owned_kitty(from, kitty_id);
+        // 记录新的所有者的记录
+        Self::add_owned_kitty(to, kitty_id);
+        <KittyPrices<T>>::remove(kitty_id);
+        <DutchAuctions<T>>::remove(kitty_id);
+        <KittyApprovals<T>>::remove(kitty_id);
+        <SiringOffers<T>>::remove(kitty_id);
+        <LeaseOffers<T>>::remove(kitty_id);
+        Ok(())
+    }
+
+    // 租期结束, 把kitty归还出租人
+    // 即使出租人拥有的kitty数量已达上限也归还, 保证租约总能结束
+    fn end_lease(kitty_id: T::KittyIndex) {
+        let lease = match <Leases<T>>::take(kitty_id) {
+            Some(lease) => lease,
+            None => return,
+        };
+        <KittyOwner<T>>::insert(kitty_id, &lease.lessor);
+        Self::remove_owned_kitty(&lease.lessee, kitty_id);
+        Self::add_owned_kitty(&lease.lessor, kitty_id);
+        // 清除承租人设置的授权和邀约
+        <KittyApprovals<T>>::remove(kitty_id);
+        <SiringOffers<T>>::remove(kitty_id);
+        <LeaseOffers<T>>::remove(kitty_id);
+
+        Self::deposit_event(RawEvent::LeaseEnded(lease.lessor, lease.lessee, kitty_id));
+    }
+
+    // 某个账号拥有的所有kitty
+    pub fn kitties_of(owner: &T::AccountId) -> vec::Vec<T::KittyIndex> {
+        let mut kitties: vec::Vec<T::KittyIndex> = <AccountKitties<T>>::iter_prefix(owner)
+            .map(|(kitty_id, _)| kitty_id)
+            .collect();
+        kitties.sort();
+        kitties
+    }
+
+    // 记录 owner 拥有 kitty_id
+    fn add_owned_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+        if !<AccountKitties<T>>::contains_key(owner, kitty_id) {
+            <AccountKitties<T>>::insert(owner, kitty_id, ());
+            <OwnedKittiesCount<T>>::mutate(owner, |count| *count = count.saturating_add(1));
+        }
+    }
+
+    // 移除 owner 拥有 kitty_id 的记录
+    fn remove_owned_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+        if <AccountKitties<T>>::take(owner, kitty_id).is_some() {
+            <OwnedKittiesCount<T>>::mutate(owner, |count| *count = count.saturating_sub(1));
+        }
+    }
+
+    // 两只kitty共同的孩子, 与参数顺序无关
+    pub fn children(a: T::KittyIndex, b: T::KittyIndex) -> vec::Vec<T::KittyIndex> {
+        let mut children = Self::kitty_children(a, b);
+        if a != b {
+            children.extend(Self::kitty_children(b, a));
+        }
+        children.sort();
+        children
+    }
+
+    // kitty的兄弟姐妹, 即父母其他的孩子
+    // 融合而来的kitty不是繁殖出生的, 没有兄弟姐妹, 被融合kitty的孩子不算作它的兄弟姐妹
+    pub fn siblings(kitty_id: T::KittyIndex) -> vec::Vec<T::KittyIndex> {
+        if Self::is_fused(kitty_id) {
+            return vec::Vec::new();
+        }
+        match Self::kitties(kitty_id).map(|kitty| kitty.parents_ids()) {
+            Some((Some(father), Some(mother))) => {
+                let mut siblings = Self::children(father, mother);
+                siblings.retain(|&temp| temp != kitty_id);
+                siblings
+            }
+            _ => vec::Vec::new(),
+        }
+    }
+
+    // kitty的代数
+    pub fn kitty_generation(kitty_id: T::KittyIndex) -> Option<u32> {
+        Self::kitties(kitty_id).map(|kitty| kitty.generation())
+    }
+
+    // kitty的稀有度分数
+    pub fn kitty_rarity(kitty_id: T::KittyIndex) -> Option<u32> {
+        Self::kitties(kitty_id).map(|kitty| kitty.rarity())
+    }
+
+    // kitty的战斗属性
+    pub fn kitty_stats(kitty_id: T::KittyIndex) -> Option<Stats> {
+        Self::kitties(kitty_id).map(|kitty| stats::stats(&kitty.dna_data()))
+    }
+
+    // 参赛kitty的战斗属性, kitty不存在时所有属性为0
+    fn stats_of(kitty_id: T::KittyIndex) -> Stats {
+        Self::kitty_stats(kitty_id).unwrap_or_else(|| stats::stats(&[0; 16]))
+    }
+
+    // kitty的外观特征
+    pub fn kitty_phenotype(kitty_id: T::KittyIndex) -> Option<Phenotype> {
+        Self::kitties(kitty_id).map(|kitty| kitty.phenotype())
+    }
+
+    // kitty 当前的出售价格, 一口价或荷兰式拍卖的当前价格, 未出售返回None
+    pub fn current_price(kitty_id: T::KittyIndex) -> Option<BalanceOf<T>> {
+        if let Some(price) = Self::kitty_price(kitty_id) {
+            return Some(price);
+        }
+        Self::dutch_auction(kitty_id)
+            .map(|auction| Self::dutch_price_at(&auction, <frame_system::Module<T>>::block_number()))
+    }
+
+    // 计算荷兰式拍卖在 now 区块时的价格
+    fn dutch_price_at(auction: &DutchAuctionOf<T>, now: T::BlockNumber) -> BalanceOf<T> {
+        let elapsed = now.saturating_sub(auction.start);
+        if elapsed >= auction.duration {
+            return auction.floor_price;
+        }
+        let range = auction.start_price.saturating_sub(auction.floor_price);
+        let discount = Perbill::from_rational_approximation(elapsed, auction.duration) * range;
+        auction.start_price.saturating_sub(discount)
+    }
+
+    // 从所有存储中删除kitty并更新家族信息, 退还元数据质押, 返回该kitty的创建质押记录
+    // 它的孩子仍然通过 parents_ids 记录它, 以保留血统
+    fn remove_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex) -> Option<(T::AccountId, BalanceOf<T>)> {
+        if let Some(kitty) = <Kitties<T>>::take(kitty_id) {
+            if let (Some(father), Some(mother)) = kitty.parents_ids() {
+                <KittiesChildren<T>>::mutate(father, mother, |val| val.retain(|&temp| temp != kitty_id));
+            }
+        }
+        <KittyOwner<T>>::remove(kitty_id);
+        Self::remove_owned_kitty(owner, kitty_id);
+        <KittyPrices<T>>::remove(kitty_id);
+        <DutchAuctions<T>>::remove(kitty_id);
+        <KittyApprovals<T>>::remove(kitty_id);
+        <SiringOffers<T>>::remove(kitty_id);
+        <LeaseOffers<T>>::remove(kitty_id);
+        <FusedKitties<T>>::remove(kitty_id);
+        <KittyReadyAt<T>>::remove(kitty_id);
+        if let Some(old) = <Metadata<T>>::take(kitty_id) {
+            T::Currency::unreserve(&old.depositor, old.deposit);
+        }
+        <KittyDeposits<T>>::take(kitty_id)
+    }
+
+    // 一步繁殖时突变使用的另一次随机抽取
+    fn mutation_seed(sender: &T::AccountId) -> [u8; 32] {
+        (
+            T::Randomness::random(b"kitties/mutation"),
+            &sender,
+            <frame_system::Module<T>>::extrinsic_index(),
+        ).using_encoded(blake2_256)
+    }
+
+    // 揭示繁殖时, 由盐和揭示区块记录的随机数得到 selector 和突变种子, 随机数已删除时返回 None
+    fn revealed_seeds(sender: &T::AccountId, reveal_at: T::BlockNumber, salt: &[u8; 32]) -> Option<([u8; 16], [u8; 32])> {
+        let random = Self::reveal_randomness(reveal_at)?;
+        let seed = (random, &sender, salt).using_encoded(blake2_256);
+        Some(((&seed, b"selector").using_encoded(blake2_128), (&seed, b"mutation").using_encoded(blake2_256)))
+    }
+
+    // 没收繁殖承诺的保证金
+    fn forfeit_breed_bond(who: &T::AccountId, bond: BalanceOf<T>) {
+        let (_, missing) = T::Currency::slash_reserved(who, bond);
+        Self::deposit_event(RawEvent::BreedBondForfeited(who.clone(), bond.saturating_sub(missing)));
+    }
+
+    // 繁殖承诺, 客户端用同样的方式计算后提交
+    pub fn breed_commitment_hash(kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex, salt: &[u8; 32]) -> T::Hash {
+        T::Hashing::hash_of(&(kitty_id_1, kitty_id_2, salt))
+    }
+
+    // 每个dna字节以 MutationRate 的概率翻转其中一位, 返回是否发生了突变
+    fn mutate_dna(seed: &[u8; 32], dna: &mut [u8; 16]) -> bool {
+        let rate = T::MutationRate::get().deconstruct();
+        if rate == 0 {
+            return false;
+        }
+
+        let mut mutated = false;
+        for i in 0..dna.len() {
+            let roll = (&seed, i as u8).using_encoded(blake2_256);
+            let chance = u32::from_le_bytes([roll[0], roll[1], roll[2], roll[3]]) % 1_000_000;
+            if chance < rate {
+                dna[i] ^= 1 << (roll[4] % 8);
+                mutated = true;
+            }
+        }
+        mutated
+    }
+
+    // 用给定的dna创建一只初代kitty并记录质押, 质押需要由调用者事先完成
+    fn mint_kitty(owner: &T::AccountId, dna: [u8; 16], deposit: BalanceOf<T>) -> DispatchResult {
+        let kitty_id = Self::next_kitty_id()?;
+
+        // 创建新的kitty
+        let mut new_kitty = Kitty::new();
+        new_kitty.set_kitty_id(kitty_id);
+        new_kitty.set_dna_data(dna);
+        let rarity = new_kitty.rarity();
+
+        Self::insert_kitty(owner, kitty_id, new_kitty)?;
+        <KittyDeposits<T>>::insert(kitty_id, (owner.clone(), deposit));
+
+        Self::deposit_event(RawEvent::Created(owner.clone(), kitty_id, 0, rarity));
+        Ok(())
+    }
+
+    // 所有者的kitty数量已达上限时失败且不修改任何存储
+	fn insert_kitty(owner: &T::AccountId, kitty_id: T::KittyIndex, kitty: Kitty<T::KittyIndex>) -> DispatchResult {
+        Self::ensure_can_own(owner)?;
 		<Kitties<T>>::insert(kitty_id, kitty);
         <KittiesCount<T>>::put(kitty_id + 1.into());
         // 设置kitty所有者
         <KittyOwner<T>>::insert(kitty_id, owner);
-        if <OwnedKitties<T>>::contains_key(&owner) {
-            // 已经存在 继续添加 
-            <OwnedKitties<T>>::mutate(owner, |val| val.push(kitty_id));
-        } else {
-           // 不存大 创建新的
-           <OwnedKitties<T>>::insert(owner, vec![kitty_id]);
-        }
-        
+        Self::add_owned_kitty(owner, kitty_id);
+        Ok(())
 	}
-    // 更新孩子信息
+    // 更新孩子信息, father 和 mother 由性别决定
     fn update_kitties_children(
         children: T::KittyIndex,
         father: T::KittyIndex,
@@ -201,67 +1588,146 @@ impl<T: Trait> Module<T> {
             <KittiesChildren<T>>::insert(father, mother, vec![children]);
         }
     }
-    // 更新兄弟信息
-    fn update_kitties_brother(new_kitty: &Kitty<T::KittyIndex>) {
-        if let (Some(father), Some(mother)) = new_kitty.parents_ids {
-            match new_kitty.kitty_id {
-                Some(kitty_id) => {
-                    if <KittiesChildren<T>>::contains_key(father, mother) {
-                        let val: vec::Vec<T::KittyIndex> = <KittiesChildren<T>>::get(father, mother);
-                        let reserve_val: vec::Vec<T::KittyIndex> =
-                            val.into_iter().filter(|&val| val != kitty_id).collect();
-                        <KittiesBrother<T>>::insert(kitty_id, reserve_val);
-                    } else {
-                        <KittiesBrother<T>>::insert(kitty_id, vec::Vec::<T::KittyIndex>::new());
+    // kitty 自身以及 generations 代以内的所有祖先
+    fn ancestors(kitty_id: T::KittyIndex, generations: u32) -> BTreeSet<T::KittyIndex> {
+        let mut ancestors = BTreeSet::new();
+        ancestors.insert(kitty_id);
+        let mut current = vec![kitty_id];
+        for _ in 0..generations {
+            let mut parents = vec::Vec::new();
+            for id in current {
+                if let Some(kitty) = Self::kitties(id) {
+                    let (father, mother) = kitty.parents_ids();
+                    for parent in [father, mother].iter().flatten() {
+                        if ancestors.insert(*parent) {
+                            parents.push(*parent);
+                        }
                     }
                 }
-                None => {}
             }
-            //let kitty_id: T::KittyIndex = new_kitty.kitty_id.unwrap();
-            
-        } 
+            if parents.is_empty() {
+                break;
+            }
+            current = parents;
+        }
+        ancestors
+    }
+
+    // 近亲检查时父母各自最多读取的祖先数量, 每往上一代祖先数量翻倍
+    fn max_ancestors() -> u32 {
+        let generations = T::InbreedingDepth::get().saturating_add(1).min(30);
+        (2u32 << generations) - 2
     }
 
-	fn do_breed(sender: T::AccountId, kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> sp_std::result::Result<T::KittyIndex, DispatchError>  {
-		let mut kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
-		let mut kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
+    // 判断两只kitty是否为近亲: 父母/子女, 兄弟姐妹, 或 InbreedingDepth 层以内的堂表亲
+    // 融合而来的kitty的dna来自被融合的kitty, 祖先按 parents_ids 中被融合的kitty计算,
+    // 因此它与被融合kitty的孩子有共同的祖先, 仍然不能繁殖
+    fn is_related(kitty_id_1: T::KittyIndex, kitty_id_2: T::KittyIndex) -> bool {
+        // 兄弟姐妹有共同的父母, n 级堂表亲有共同的第 n+1 代祖先
+        let generations = T::InbreedingDepth::get().saturating_add(1);
+        let ancestors_1 = Self::ancestors(kitty_id_1, generations);
+        Self::ancestors(kitty_id_2, generations).iter().any(|id| ancestors_1.contains(id))
+    }
+
+    // selector 决定从父母哪一方继承每一位, mutation_seed 用于突变
+	fn do_breed(
+        sender: T::AccountId,
+        kitty_id_1: T::KittyIndex,
+        kitty_id_2: T::KittyIndex,
+        selector: [u8; 16],
+        mutation_seed: [u8; 32],
+    ) -> sp_std::result::Result<T::BlockNumber, DispatchError>  {
+		let kitty1 = Self::kitties(kitty_id_1).ok_or(Error::<T>::InvalidKittyId)?;
+		let kitty2 = Self::kitties(kitty_id_2).ok_or(Error::<T>::InvalidKittyId)?;
 
 		ensure!(kitty_id_1 != kitty_id_2, Error::<T>::RequireDifferentParent);
+        Self::ensure_kitty_can_breed(kitty_id_1)?;
+        Self::ensure_kitty_can_breed(kitty_id_2)?;
+        ensure!(kitty1.gender() != kitty2.gender(), Error::<T>::SameGender);
+        ensure!(!Self::is_related(kitty_id_1, kitty_id_2), Error::<T>::RelatedParents);
+
+        let now = <frame_system::Module<T>>::block_number();
+        ensure!(now >= Self::kitty_ready_at(kitty_id_1), Error::<T>::KittyBusy);
+        ensure!(now >= Self::kitty_ready_at(kitty_id_2), Error::<T>::KittyBusy);
+        // 出生时还会再检查一次, 期间数量达到上限时出生失败并退还质押
+        Self::ensure_can_own(&sender)?;
 
-		let new_kitty_id = Self::next_kitty_id()?;
+		Self::next_kitty_id()?;
 
-		let kitty1_dna = kitty1.dna_data;
-		let kitty2_dna = kitty2.dna_data;
+        // 父母的顺序与参数顺序无关, 总是 (父亲, 母亲)
+        let (father_id, mut father, mother_id, mut mother) = match kitty1.gender() {
+            Gender::Male => (kitty_id_1, kitty1, kitty_id_2, kitty2),
+            Gender::Female => (kitty_id_2, kitty2, kitty_id_1, kitty1),
+        };
 
-		// 生成128位的随机值
-		let selector = Self::random_value(&sender);
-		let mut new_dna = [0u8; 16];
+		let father_dna = father.dna_data;
+		let mother_dna = mother.dna_data;
 
 		// 生成新的kitty
-		for i in 0..kitty1_dna.len() {
-			new_dna[i] = combine_dna(kitty1_dna[i], kitty2_dna[i], selector[i]);
-		}
+		let mut new_dna = T::Genetics::recombine(&father_dna, &mother_dna, &selector);
+        let mutated = Self::mutate_dna(&mutation_seed, &mut new_dna);
+
         let mut new_kitty = Kitty::new();
         // 新kitty设置dna信息
         new_kitty.set_dna_data(new_dna);
         // 新kitty设置父母信息
-        new_kitty.set_parents_ids(Some(kitty_id_1), Some(kitty_id_2));
-		
+        new_kitty.set_parents_ids(Some(father_id), Some(mother_id));
+        new_kitty.set_generation(father.generation.max(mother.generation).saturating_add(1));
+        new_kitty.set_mutated(mutated);
+
+        let deposit = T::NewKittyReserve::get();
+        T::Currency::reserve(&sender, deposit).map_err(|_| Error::<T>::MoneyNotEnough)?;
+
         // 相互设置配偶信息
-        kitty1.set_spouse_id(kitty_id_2);
-        kitty2.set_spouse_id(kitty_id_1);
+        father.set_spouse_id(mother_id);
+        mother.set_spouse_id(father_id);
+
+        // 怀孕期间父母不能繁殖, 出生后还需要按代数冷却
+        // 本区块的 on_initialize 已经执行过, 怀孕期为0时也要到下一个区块出生
+        let due = now.saturating_add(T::GestationPeriod::get().max(1u32.into()));
+        <KittyReadyAt<T>>::insert(father_id, due.saturating_add(Self::breed_cooldown(&father)));
+        <KittyReadyAt<T>>::insert(mother_id, due.saturating_add(Self::breed_cooldown(&mother)));
+        <Kitties<T>>::insert(father_id, father);
+        <Kitties<T>>::insert(mother_id, mother);
 
-        // 更新double map 父母对应的孩子index
-        Self::update_kitties_children(new_kitty_id, kitty_id_1, kitty_id_2);
+        // kitty id 在出生时才分配
+        <PendingBirths<T>>::mutate(due, |val| val.push((sender, new_kitty, deposit)));
 
-        // 更新brother
-        Self::update_kitties_brother(&new_kitty);
+		Ok(due)
+	}
+
+    // 冷却时间随代数增长, 第 n 代kitty 的冷却时间为 BreedCooldown * (n + 1)
+    fn breed_cooldown(kitty: &Kitty<T::KittyIndex>) -> T::BlockNumber {
+        T::BreedCooldown::get().saturating_mul(kitty.generation.saturating_add(1).into())
+    }
+
+    // 到期出生: 分配 kitty id 并更新家族信息
+    fn give_birth(owner: T::AccountId, mut new_kitty: Kitty<T::KittyIndex>, deposit: BalanceOf<T>) {
+        let new_kitty_id = match Self::next_kitty_id() {
+            Ok(kitty_id) => kitty_id,
+            Err(_) => return Self::birth_failed(owner, &new_kitty, deposit),
+        };
+        new_kitty.set_kitty_id(new_kitty_id);
 
-        T::Currency::reserve(&sender, T::NewKittyReserve::get()).map_err(|_| Error::<T>::MoneyNotEnough)?;
         // 添加kittyid->kitty映射
-        Self::insert_kitty(&sender, new_kitty_id, new_kitty);
-        
+        if Self::insert_kitty(&owner, new_kitty_id, new_kitty.clone()).is_err() {
+            return Self::birth_failed(owner, &new_kitty, deposit);
+        }
+        <KittyDeposits<T>>::insert(new_kitty_id, (owner.clone(), deposit));
 
-		Ok(new_kitty_id)
-	}
-}
\ No newline at end of file
+        if let (Some(father), Some(mother)) = new_kitty.parents_ids() {
+            // 更新double map 父母对应的孩子index, 键的顺序总是 (父亲, 母亲)
+            Self::update_kitties_children(new_kitty_id, father, mother);
+        }
+
+        Self::deposit_event(RawEvent::Born(owner, new_kitty_id, new_kitty.generation(), new_kitty.rarity()));
+    }
+
+    // 无法出生: 退还质押
+    fn birth_failed(owner: T::AccountId, new_kitty: &Kitty<T::KittyIndex>, deposit: BalanceOf<T>) {
+        T::Currency::unreserve(&owner, deposit);
+        if let (Some(father), Some(mother)) = new_kitty.parents_ids() {
+            Self::deposit_event(RawEvent::BirthFailed(owner, father, mother));
+        }
+    }
+}
@@ -0,0 +1,122 @@
+// 存储迁移, 由 on_runtime_upgrade 根据 StorageVersion 调用
+
+use codec::{Encode, Decode};
+use frame_support::{
+    storage::migration::{StorageIterator, remove_storage_prefix, put_storage_value},
+    traits::Get, weights::Weight, StorageValue, StorageMap, StorageDoubleMap, IterableStorageMap,
+};
+use sp_std::{cell::Cell, vec::Vec};
+use crate::{
+    Trait, Kitty, BalanceOf, Releases, StorageVersion, AccountKitties, OwnedKittiesCount, Kitties, PendingBirths,
+};
+
+// V1 中只有id, 父母, 配偶和dna的 Kitty
+#[derive(Encode, Decode)]
+struct V1Kitty<T> {
+    kitty_id: Option<T>,
+    parents_ids: (Option<T>, Option<T>),
+    spouse_id: Option<T>,
+    dna_data: [u8; 16],
+}
+
+impl<T> From<V1Kitty<T>> for OldKitty<T> {
+    fn from(old: V1Kitty<T>) -> Self {
+        OldKitty {
+            kitty_id: old.kitty_id,
+            parents_ids: old.parents_ids,
+            spouse_id: old.spouse_id,
+            dna_data: old.dna_data,
+            generation: 0,
+            mutated: false,
+        }
+    }
+}
+
+// V1 -> V2: Kitty 增加代数和突变标记, 已有的kitty都按初代且未突变处理;
+// V1 创建和繁殖时没有设置 kitty_id, 从存储键中取回;
+// 把 OwnedKitties 的 Vec 拆到 AccountKitties 双键映射中并记录数量, 删除 KittiesBrother
+pub fn migrate_to_v2<T: Trait>() -> Weight {
+    let mut reads: Weight = 1;
+    let mut writes: Weight = 2;
+
+    // 先读出全部旧的kitty, 避免边遍历边写入同一个前缀
+    let kitties: Vec<(Vec<u8>, V1Kitty<T::KittyIndex>)> =
+        StorageIterator::<V1Kitty<T::KittyIndex>>::new(b"Kitties", b"Kitties").collect();
+    for (key, kitty) in kitties {
+        reads += 1;
+        writes += 1;
+        let mut kitty = OldKitty::from(kitty);
+        // blake2_128_concat 的键为 16 字节哈希加上编码后的 kitty id
+        if let Some(kitty_id) = key.get(16..).and_then(|mut raw| T::KittyIndex::decode(&mut raw).ok()) {
+            kitty.kitty_id = Some(kitty_id);
+        }
+        put_storage_value(b"Kitties", b"Kitties", &key, kitty);
+    }
+
+    for (key, kitties) in StorageIterator::<Vec<T::KittyIndex>>::new(b"Kitties", b"OwnedKitties").drain() {
+        reads += 1;
+        writes += 1;
+        // blake2_128_concat 的键为 16 字节哈希加上编码后的账号
+        let owner = match key.get(16..).and_then(|mut raw| T::AccountId::decode(&mut raw).ok()) {
+            Some(owner) => owner,
+            None => continue,
+        };
+        for kitty_id in kitties.iter() {
+            <AccountKitties<T>>::insert(&owner, kitty_id, ());
+        }
+        <OwnedKittiesCount<T>>::insert(&owner, kitties.len() as u32);
+        writes += kitties.len() as Weight + 1;
+    }
+
+    // 兄弟姐妹改为通过 KittiesChildren 计算
+    remove_storage_prefix(b"Kitties", b"KittiesBrother", &[]);
+
+    StorageVersion::put(Releases::V2);
+
+    T::DbWeight::get().reads_writes(reads, writes)
+}
+
+// V2 中没有稀有度分数的 Kitty
+#[derive(Encode, Decode)]
+struct OldKitty<T> {
+    kitty_id: Option<T>,
+    parents_ids: (Option<T>, Option<T>),
+    spouse_id: Option<T>,
+    dna_data: [u8; 16],
+    generation: u32,
+    mutated: bool,
+}
+
+impl<T: Copy> From<OldKitty<T>> for Kitty<T> {
+    fn from(old: OldKitty<T>) -> Self {
+        let mut kitty = Kitty {
+            kitty_id: old.kitty_id,
+            parents_ids: old.parents_ids,
+            spouse_id: old.spouse_id,
+            dna_data: old.dna_data,
+            generation: old.generation,
+            mutated: old.mutated,
+            rarity: 0,
+        };
+        kitty.update_rarity();
+        kitty
+    }
+}
+
+// V2 -> V3: 为已有的kitty和未出生的kitty计算稀有度分数
+pub fn migrate_to_v3<T: Trait>() -> Weight {
+    let count: Cell<Weight> = Cell::new(0);
+
+    <Kitties<T>>::translate::<OldKitty<T::KittyIndex>, _>(|_, old| {
+        count.set(count.get() + 1);
+        Some(old.into())
+    });
+    <PendingBirths<T>>::translate::<Vec<(T::AccountId, OldKitty<T::KittyIndex>, BalanceOf<T>)>, _>(|_, births| {
+        count.set(count.get() + 1);
+        Some(births.into_iter().map(|(owner, old, deposit)| (owner, old.into(), deposit)).collect())
+    });
+
+    StorageVersion::put(Releases::V3);
+
+    T::DbWeight::get().reads_writes(count.get() + 1, count.get() + 1)
+}
@@ -12,6 +12,7 @@ use sp_runtime::{
 };
 use frame_system;
 use balances;
+use std::cell::RefCell;
 
 impl_outer_origin! {
     pub enum Origin for Test {}
@@ -83,8 +84,54 @@ impl balances::Trait for Test {
 }
 
 type Randomness = pallet_randomness_collective_flip::Module<Test>;
+
+thread_local! {
+    static DEPOSIT_POLICY: RefCell<DepositPolicy> = RefCell::new(DepositPolicy::CreatorPays);
+    static MUTATION_RATE: RefCell<Permill> = RefCell::new(Permill::from_parts(0));
+}
+pub struct KittyDepositPolicy;
+impl Get<DepositPolicy> for KittyDepositPolicy {
+    fn get() -> DepositPolicy {
+        DEPOSIT_POLICY.with(|v| *v.borrow())
+    }
+}
+// 修改质押模式, 默认为 CreatorPays
+pub fn set_deposit_policy(policy: DepositPolicy) {
+    DEPOSIT_POLICY.with(|v| *v.borrow_mut() = policy);
+}
+pub struct MutationRate;
+impl Get<Permill> for MutationRate {
+    fn get() -> Permill {
+        MUTATION_RATE.with(|v| *v.borrow())
+    }
+}
+// 修改突变概率, 默认不突变
+pub fn set_mutation_rate(rate: Permill) {
+    MUTATION_RATE.with(|v| *v.borrow_mut() = rate);
+}
+// 是否允许一步繁殖, 默认允许
+pub fn set_allow_instant_breed(allow: bool) {
+    InstantBreedAllowed::put(allow);
+}
 parameter_types! {
     pub const NewKittyReserve: u64 = 5_000;
+    pub const MetadataDepositBase: u64 = 100;
+    pub const MetadataDepositPerByte: u64 = 10;
+    pub const MaxNameLength: u32 = 16;
+    pub const MaxMetadataLength: u32 = 64;
+    pub const MaxKittiesPerAccount: u32 = 10;
+    pub const MaxBatchSize: u32 = 5;
+    pub const MaxAuctionsPerBlock: u32 = 2;
+    pub const MaxLeasesPerBlock: u32 = 2;
+    pub const MaxTournamentSize: u32 = 8;
+    pub const MaxTournamentsPerBlock: u32 = 2;
+    pub const TournamentDeposit: u64 = 1_000;
+    pub const InbreedingDepth: u32 = 1;
+    pub const GestationPeriod: u64 = 5;
+    pub const BreedCooldown: u64 = 3;
+    pub const RevealDelay: u64 = 2;
+    pub const RevealWindow: u64 = 5;
+    pub const BreedBond: u64 = 500;
 }
     impl Trait for Test {
     type Event = TestEvent;
@@ -92,12 +139,33 @@ parameter_types! {
     type KittyIndex = u32;
     type NewKittyReserve = NewKittyReserve;
     type Currency = balances::Module<Self>;
+    type DepositPolicy = KittyDepositPolicy;
+    type MetadataDepositBase = MetadataDepositBase;
+    type MetadataDepositPerByte = MetadataDepositPerByte;
+    type MaxNameLength = MaxNameLength;
+    type MaxMetadataLength = MaxMetadataLength;
+    type MaxKittiesPerAccount = MaxKittiesPerAccount;
+    type MaxBatchSize = MaxBatchSize;
+    type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+    type MaxLeasesPerBlock = MaxLeasesPerBlock;
+    type MaxTournamentSize = MaxTournamentSize;
+    type MaxTournamentsPerBlock = MaxTournamentsPerBlock;
+    type TournamentDeposit = TournamentDeposit;
+    type InbreedingDepth = InbreedingDepth;
+    type GestationPeriod = GestationPeriod;
+    type BreedCooldown = BreedCooldown;
+    type MutationRate = MutationRate;
+    type RevealDelay = RevealDelay;
+    type RevealWindow = RevealWindow;
+    type BreedBond = BreedBond;
+    type Genetics = crate::genetics::Mendelian;
+    type WeightInfo = ();
 }
 
 pub type Kitties = Module<Test>;
 pub type System = frame_system::Module<Test>;
 
-fn run_to_block(n: u64) {
+pub fn run_to_block(n: u64) {
     while System::block_number() < n {
         Kitties::on_finalize(System::block_number());
         System::on_finalize(System::block_number());
@@ -115,7 +183,12 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
     .assimilate_storage(&mut t)
     .unwrap();
 
+    set_deposit_policy(DepositPolicy::CreatorPays);
+    set_mutation_rate(Permill::from_parts(0));
     let mut ext = sp_io::TestExternalities::new(t);
-    ext.execute_with(|| System::set_block_number(1));
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        set_allow_instant_breed(true);
+    });
     ext
 }
\ No newline at end of file
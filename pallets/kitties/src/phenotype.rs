@@ -0,0 +1,207 @@
+// kitty 的外观特征(表现型), 由 dna 确定性地解码得到
+//
+// dna 第 2 到第 9 个字节是四对等位基因, 每对决定一个特征:
+// 第一个等位基因来自父亲, 第二个来自母亲. 等位基因最高位为1表示显性,
+// 显性基因优先表达; 两个等位基因同为显性或同为隐性时, 表达数值较小的一个.
+// 只有两个等位基因都是隐性时才会表达隐性特征, 表达的隐性特征越多越稀有.
+
+use codec::{Encode, Decode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+// 显性标记
+pub const DOMINANT: u8 = 0x80;
+// 体色基因的位置
+pub const BODY_COLOR_GENE: usize = 2;
+// 眼睛颜色基因的位置
+pub const EYE_COLOR_GENE: usize = 4;
+// 花纹基因的位置
+pub const PATTERN_GENE: usize = 6;
+// 配饰基因的位置
+pub const ACCESSORY_GENE: usize = 8;
+// 所有特征基因, 繁殖时按等位基因遗传
+pub const TRAIT_GENES: [usize; 4] = [BODY_COLOR_GENE, EYE_COLOR_GENE, PATTERN_GENE, ACCESSORY_GENE];
+
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum BodyColor {
+    Ginger,
+    Black,
+    White,
+    Grey,
+    Cream,
+    Cinnamon,
+    Lilac,
+    Calico,
+}
+
+impl BodyColor {
+    fn from_allele(allele: u8) -> Self {
+        match allele % 8 {
+            0 => BodyColor::Ginger,
+            1 => BodyColor::Black,
+            2 => BodyColor::White,
+            3 => BodyColor::Grey,
+            4 => BodyColor::Cream,
+            5 => BodyColor::Cinnamon,
+            6 => BodyColor::Lilac,
+            _ => BodyColor::Calico,
+        }
+    }
+}
+
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum EyeColor {
+    Green,
+    Blue,
+    Amber,
+    Copper,
+    Hazel,
+    Violet,
+}
+
+impl EyeColor {
+    fn from_allele(allele: u8) -> Self {
+        match allele % 6 {
+            0 => EyeColor::Green,
+            1 => EyeColor::Blue,
+            2 => EyeColor::Amber,
+            3 => EyeColor::Copper,
+            4 => EyeColor::Hazel,
+            _ => EyeColor::Violet,
+        }
+    }
+}
+
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Pattern {
+    Solid,
+    Tabby,
+    Spotted,
+    Tortoiseshell,
+    Pointed,
+    Tuxedo,
+}
+
+impl Pattern {
+    fn from_allele(allele: u8) -> Self {
+        match allele % 6 {
+            0 => Pattern::Solid,
+            1 => Pattern::Tabby,
+            2 => Pattern::Spotted,
+            3 => Pattern::Tortoiseshell,
+            4 => Pattern::Pointed,
+            _ => Pattern::Tuxedo,
+        }
+    }
+}
+
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Accessory {
+    Plain,
+    Bow,
+    Collar,
+    Hat,
+    Glasses,
+}
+
+impl Accessory {
+    fn from_allele(allele: u8) -> Self {
+        match allele % 5 {
+            0 => Accessory::Plain,
+            1 => Accessory::Bow,
+            2 => Accessory::Collar,
+            3 => Accessory::Hat,
+            _ => Accessory::Glasses,
+        }
+    }
+}
+
+// 稀有度, 由表达的隐性特征数量决定
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum RarityTier {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl RarityTier {
+    fn from_recessive_count(count: u8) -> Self {
+        match count {
+            0 => RarityTier::Common,
+            1 => RarityTier::Uncommon,
+            2 => RarityTier::Rare,
+            3 => RarityTier::Epic,
+            _ => RarityTier::Legendary,
+        }
+    }
+}
+
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Phenotype {
+    pub body_color: BodyColor,
+    pub eye_color: EyeColor,
+    pub pattern: Pattern,
+    pub accessory: Accessory,
+    pub rarity: RarityTier,
+}
+
+// 一对等位基因中被表达的一个(去掉显性标记), 以及是否表达了隐性特征
+pub fn expressed_allele(dna: &[u8; 16], gene: usize) -> (u8, bool) {
+    let (a, b) = (dna[gene], dna[gene + 1]);
+    match (a & DOMINANT != 0, b & DOMINANT != 0) {
+        (true, false) => (a & !DOMINANT, false),
+        (false, true) => (b & !DOMINANT, false),
+        (true, true) => ((a & !DOMINANT).min(b & !DOMINANT), false),
+        (false, false) => (a.min(b), true),
+    }
+}
+
+// 每个表达隐性特征的基因的稀有度分数
+pub const RECESSIVE_SCORE: u32 = 100;
+// 初代kitty的代数分数, 每多一代减半
+pub const GENERATION_SCORE: u32 = 64;
+
+// 稀有度分数, 表达的隐性特征越多, 代数越小, 分数越高
+pub fn rarity_score(dna: &[u8; 16], generation: u32) -> u32 {
+    let recessive_count = TRAIT_GENES.iter()
+        .filter(|&&gene| expressed_allele(dna, gene).1)
+        .count() as u32;
+    recessive_count * RECESSIVE_SCORE + GENERATION_SCORE.checked_shr(generation).unwrap_or(0)
+}
+
+// 从 dna 解码表现型
+pub fn phenotype(dna: &[u8; 16]) -> Phenotype {
+    let (body_color, body_recessive) = expressed_allele(dna, BODY_COLOR_GENE);
+    let (eye_color, eye_recessive) = expressed_allele(dna, EYE_COLOR_GENE);
+    let (pattern, pattern_recessive) = expressed_allele(dna, PATTERN_GENE);
+    let (accessory, accessory_recessive) = expressed_allele(dna, ACCESSORY_GENE);
+    let recessive_count = [body_recessive, eye_recessive, pattern_recessive, accessory_recessive]
+        .iter()
+        .filter(|&&recessive| recessive)
+        .count() as u8;
+
+    Phenotype {
+        body_color: BodyColor::from_allele(body_color),
+        eye_color: EyeColor::from_allele(eye_color),
+        pattern: Pattern::from_allele(pattern),
+        accessory: Accessory::from_allele(accessory),
+        rarity: RarityTier::from_recessive_count(recessive_count),
+    }
+}
+
+// 孟德尔遗传: 孩子的每对等位基因中, 第一个随机取自父亲的一对, 第二个随机取自母亲的一对
+pub fn inherit_genes(father: &[u8; 16], mother: &[u8; 16], selector: &[u8; 16], child: &mut [u8; 16]) {
+    for &gene in TRAIT_GENES.iter() {
+        child[gene] = father[gene + (selector[gene] & 1) as usize];
+        child[gene + 1] = mother[gene + (selector[gene + 1] & 1) as usize];
+    }
+}
@@ -0,0 +1,51 @@
+// kitty 的战斗属性, 由 dna 确定性地得到
+//
+// dna 第 11 到第 16 个字节没有用于外观特征, 每两个字节相加得到一项属性,
+// 每项属性的取值范围是 0 到 510.
+
+use codec::{Encode, Decode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+// 攻击基因的位置
+pub const ATTACK_GENE: usize = 10;
+// 防御基因的位置
+pub const DEFENSE_GENE: usize = 12;
+// 速度基因的位置
+pub const SPEED_GENE: usize = 14;
+
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Stats {
+    pub attack: u32,
+    pub defense: u32,
+    pub speed: u32,
+}
+
+fn gene(dna: &[u8; 16], position: usize) -> u32 {
+    dna[position] as u32 + dna[position + 1] as u32
+}
+
+// 从dna得到战斗属性
+pub fn stats(dna: &[u8; 16]) -> Stats {
+    Stats {
+        attack: gene(dna, ATTACK_GENE),
+        defense: gene(dna, DEFENSE_GENE),
+        speed: gene(dna, SPEED_GENE),
+    }
+}
+
+// 攻击方对防守方的威力: 攻击减去对方一半的防御, 速度比对方快时有额外加成, 至少为1
+fn power(attacker: &Stats, defender: &Stats) -> u32 {
+    attacker.attack.saturating_sub(defender.defense / 2)
+        + attacker.speed.saturating_sub(defender.speed) / 4
+        + 1
+}
+
+// 两只kitty战斗, 获胜的概率与双方的威力成正比, roll 为随机数
+// 返回 true 表示 a 获胜
+pub fn battle(a: &Stats, b: &Stats, roll: u32) -> bool {
+    let power_a = power(a, b);
+    let power_b = power(b, a);
+    roll % (power_a + power_b) < power_a
+}
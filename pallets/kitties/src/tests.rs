@@ -1,7 +1,34 @@
 use crate::{Error, mock::*};
+use crate::mock::Kitties;
 use frame_support::{assert_ok, assert_noop};
 use super::*;
 
+// 修改kitty的dna 使其成为指定的性别
+fn set_gender(kitty_id: u32, gender: Gender) {
+    crate::Kitties::<Test>::mutate(kitty_id, |kitty| {
+        let kitty = kitty.as_mut().unwrap();
+        match gender {
+            Gender::Male => kitty.dna_data[0] &= !1,
+            Gender::Female => kitty.dna_data[0] |= 1,
+        }
+    });
+}
+
+// 创建指定性别的kitty
+fn create_kitty(who: u64, gender: Gender) -> u32 {
+    let kitty_id = Kitties::kitties_count();
+    assert_ok!(Kitties::create(Origin::signed(who)));
+    set_gender(kitty_id, gender);
+    kitty_id
+}
+
+// 繁殖并等待kitty出生以及父母冷却结束
+fn breed_and_wait(who: u64, kitty_id_1: u32, kitty_id_2: u32) {
+    assert_ok!(Kitties::breed(Origin::signed(who), kitty_id_1, kitty_id_2));
+    let ready = Kitties::kitty_ready_at(kitty_id_1).max(Kitties::kitty_ready_at(kitty_id_2));
+    run_to_block(ready);
+}
+
 /// 创建kitty 够足质押
 #[test]
 fn owned_kitties_can_append_values() {
@@ -11,7 +38,7 @@ fn owned_kitties_can_append_values() {
         assert_ok!(Kitties::create(Origin::signed(1)));
         assert_eq!(
         System::events()[1].event,
-        TestEvent::kitties_event(Event::<Test>::Created(1u64, 0)))
+        TestEvent::kitties_event(Event::<Test>::Created(1u64, 0, 0, Kitties::kitty_rarity(0).unwrap())))
     })
 }
 //创建kitty 不够质押
@@ -43,10 +70,14 @@ fn transfer_kitties_no_owned() {
     new_test_ext().execute_with(|| {
         run_to_block(10);
         assert_ok!(Kitties::create(Origin::signed(1)));
-        let _id = Kitties::kitties_count();
-        assert_ok!(Kitties::transfer(Origin::signed(1), 2 , 999));
+        let id = Kitties::kitties_count();
         assert_noop!(
             Kitties::transfer(Origin::signed(1), 2, 999),
+            Error::<Test>::InvalidKittyId
+        );
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2 , id - 1));
+        assert_noop!(
+            Kitties::transfer(Origin::signed(1), 2, id - 1),
             Error::<Test>::NotKittyOwner
         );
     })
@@ -90,3 +121,1506 @@ fn breed_kitty_fail_when_not_exists() {
     })
 }
 
+
+// 设置一口价 只有所有者可以设置
+#[test]
+fn set_price_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_noop!(
+            Kitties::set_price(Origin::signed(2), 0, Some(100)),
+            Error::<Test>::NotKittyOwner
+        );
+        assert_ok!(Kitties::set_price(Origin::signed(1), 0, Some(100)));
+        assert_eq!(Kitties::kitty_price(0), Some(100));
+        assert_ok!(Kitties::set_price(Origin::signed(1), 0, None));
+        assert_eq!(Kitties::kitty_price(0), None);
+    })
+}
+
+// 购买kitty 付款给卖家并转移所有权
+#[test]
+fn buy_kitty_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::set_price(Origin::signed(1), 0, Some(1_000)));
+        let seller_free = balances::Module::<Test>::free_balance(1);
+        let buyer_free = balances::Module::<Test>::free_balance(2);
+
+        assert_ok!(Kitties::buy(Origin::signed(2), 0, 1_000));
+
+        assert_eq!(Kitties::kitty_owner(0), Some(2));
+        assert_eq!(Kitties::kitties_of(&1), Vec::<u32>::new());
+        assert_eq!(Kitties::kitties_of(&2), vec![0]);
+        assert_eq!(Kitties::kitty_price(0), None);
+        assert_eq!(balances::Module::<Test>::free_balance(1), seller_free + 1_000);
+        assert_eq!(balances::Module::<Test>::free_balance(2), buyer_free - 1_000);
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::Sold(2, 1, 0, 1_000))));
+    })
+}
+
+// 购买kitty 未挂单 价格超过上限 或购买自己的kitty 失败
+#[test]
+fn buy_kitty_failed() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_noop!(Kitties::buy(Origin::signed(2), 0, 1_000), Error::<Test>::NotForSale);
+
+        assert_ok!(Kitties::set_price(Origin::signed(1), 0, Some(1_000)));
+        assert_noop!(Kitties::buy(Origin::signed(2), 0, 999), Error::<Test>::PriceTooHigh);
+        assert_noop!(Kitties::buy(Origin::signed(1), 0, 1_000), Error::<Test>::BuyOwnKitty);
+        assert_noop!(Kitties::buy(Origin::signed(6), 0, 1_000), Error::<Test>::MoneyNotEnough);
+    })
+}
+
+// 转移kitty 后挂单被清除
+#[test]
+fn transfer_clears_price() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::set_price(Origin::signed(1), 0, Some(1_000)));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+        assert_eq!(Kitties::kitty_price(0), None);
+    })
+}
+
+// 拍卖中的kitty 不能转移 出售和繁殖
+#[test]
+fn auction_locks_kitty() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 0, 100, 5));
+        assert_eq!(Kitties::kitty_auction(0), Some(0));
+
+        assert_noop!(Kitties::transfer(Origin::signed(1), 2, 0), Error::<Test>::KittyLocked);
+        assert_noop!(Kitties::set_price(Origin::signed(1), 0, Some(100)), Error::<Test>::KittyLocked);
+        assert_noop!(Kitties::breed(Origin::signed(1), 0, 1), Error::<Test>::KittyLocked);
+        assert_noop!(Kitties::create_auction(Origin::signed(1), 0, 100, 5), Error::<Test>::KittyLocked);
+    })
+}
+
+// 出价被超过时退还质押
+#[test]
+fn bid_reserves_and_refunds_outbid() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 0, 100, 5));
+
+        assert_noop!(Kitties::bid(Origin::signed(2), 0, 99), Error::<Test>::BidTooLow);
+        assert_noop!(Kitties::bid(Origin::signed(1), 0, 100), Error::<Test>::BidOwnAuction);
+        assert_noop!(Kitties::bid(Origin::signed(2), 1, 100), Error::<Test>::InvalidAuctionId);
+
+        assert_ok!(Kitties::bid(Origin::signed(2), 0, 100));
+        assert_eq!(balances::Module::<Test>::reserved_balance(2), 100);
+        assert_noop!(Kitties::bid(Origin::signed(3), 0, 100), Error::<Test>::BidTooLow);
+
+        assert_ok!(Kitties::bid(Origin::signed(3), 0, 200));
+        assert_eq!(balances::Module::<Test>::reserved_balance(2), 0);
+        assert_eq!(balances::Module::<Test>::reserved_balance(3), 200);
+        assert_eq!(Kitties::auctions(0).unwrap().best_bid, Some((3, 200)));
+    })
+}
+
+// 拍卖到期后在 on_finalize 中结算
+#[test]
+fn auction_settles_on_finalize() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 0, 100, 5));
+        assert_ok!(Kitties::bid(Origin::signed(2), 0, 300));
+        let seller_free = balances::Module::<Test>::free_balance(1);
+
+        run_to_block(15);
+        assert_eq!(Kitties::kitty_owner(0), Some(1));
+        run_to_block(16);
+        assert_noop!(Kitties::bid(Origin::signed(3), 0, 400), Error::<Test>::InvalidAuctionId);
+
+        assert_eq!(Kitties::kitty_owner(0), Some(2));
+        assert_eq!(Kitties::kitties_of(&2), vec![0]);
+        assert_eq!(Kitties::kitty_auction(0), None);
+        assert_eq!(balances::Module::<Test>::reserved_balance(2), 0);
+        assert_eq!(balances::Module::<Test>::free_balance(1), seller_free + 300);
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::AuctionSettled(0, 0, 2, 300))));
+    })
+}
+
+// 无人出价的拍卖流拍 kitty 解锁
+#[test]
+fn auction_without_bids_is_cancelled() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 0, 100, 5));
+        run_to_block(16);
+
+        assert_eq!(Kitties::kitty_owner(0), Some(1));
+        assert_eq!(Kitties::kitty_auction(0), None);
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+    })
+}
+
+// 同一个区块结束的拍卖数量有上限
+#[test]
+fn auctions_ending_per_block_are_bounded() {
+    use frame_support::traits::OnInitialize;
+
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create_batch(Origin::signed(1), 3));
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 0, 100, 5));
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 1, 100, 5));
+        assert_noop!(Kitties::create_auction(Origin::signed(1), 2, 100, 5), Error::<Test>::TooManyAuctionsEnding);
+        assert_ok!(Kitties::create_auction(Origin::signed(1), 2, 100, 6));
+
+        // 结算的权重在 on_initialize 中计入
+        let weight = Kitties::on_initialize(15);
+        assert!(weight > Kitties::on_initialize(14));
+        run_to_block(16);
+        assert_eq!(Kitties::kitty_auction(0), None);
+        assert_eq!(Kitties::kitty_auction(1), None);
+        assert_eq!(Kitties::kitty_auction(2), Some(2));
+    })
+}
+
+// 荷兰式拍卖价格随区块线性下降 到期后保持底价
+#[test]
+fn dutch_auction_price_decays() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_noop!(
+            Kitties::create_dutch_auction(Origin::signed(1), 0, 100, 200, 10),
+            Error::<Test>::InvalidPriceRange
+        );
+        assert_ok!(Kitties::create_dutch_auction(Origin::signed(1), 0, 1_000, 200, 10));
+
+        assert_eq!(Kitties::current_price(0), Some(1_000));
+        run_to_block(15);
+        assert_eq!(Kitties::current_price(0), Some(600));
+        run_to_block(20);
+        assert_eq!(Kitties::current_price(0), Some(200));
+        run_to_block(30);
+        assert_eq!(Kitties::current_price(0), Some(200));
+    })
+}
+
+// 第一个出价不低于当前价格的买家成交
+#[test]
+fn dutch_auction_buy_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create_dutch_auction(Origin::signed(1), 0, 1_000, 200, 10));
+        run_to_block(15);
+        let seller_free = balances::Module::<Test>::free_balance(1);
+
+        assert_noop!(Kitties::buy(Origin::signed(2), 0, 599), Error::<Test>::PriceTooHigh);
+        assert_ok!(Kitties::buy(Origin::signed(2), 0, 600));
+
+        assert_eq!(Kitties::kitty_owner(0), Some(2));
+        assert_eq!(Kitties::dutch_auction(0), None);
+        assert_eq!(balances::Module::<Test>::free_balance(1), seller_free + 600);
+        assert_noop!(Kitties::buy(Origin::signed(3), 0, 1_000), Error::<Test>::NotForSale);
+    })
+}
+
+// 查询孩子和兄弟姐妹
+#[test]
+fn children_and_siblings_query_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(1, Gender::Female);
+        breed_and_wait(1, 0, 1);
+        breed_and_wait(1, 0, 1);
+
+        assert_eq!(Kitties::kitties(3).unwrap().kitty_id(), Some(3));
+        assert_eq!(Kitties::kitties(3).unwrap().parents_ids(), (Some(0), Some(1)));
+        assert_eq!(Kitties::children(0, 1), vec![2, 3]);
+        assert_eq!(Kitties::children(1, 0), vec![2, 3]);
+        assert_eq!(Kitties::siblings(3), vec![2]);
+        assert_eq!(Kitties::kitties_of(&1), vec![0, 1, 2, 3]);
+    })
+}
+
+// 父母与子女 兄弟姐妹之间不能繁殖
+#[test]
+fn breed_kitty_fail_when_parent_or_sibling() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(1, Gender::Female);
+        breed_and_wait(1, 0, 1);
+        breed_and_wait(1, 0, 1);
+        set_gender(2, Gender::Female);
+        set_gender(3, Gender::Male);
+
+        assert_noop!(Kitties::breed(Origin::signed(1), 2, 0), Error::<Test>::RelatedParents);
+        assert_noop!(Kitties::breed(Origin::signed(1), 1, 3), Error::<Test>::RelatedParents);
+        assert_noop!(Kitties::breed(Origin::signed(1), 2, 3), Error::<Test>::RelatedParents);
+    })
+}
+
+// 堂表兄弟姐妹之间不能繁殖 无血缘关系可以繁殖
+#[test]
+fn breed_kitty_fail_when_cousins() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(1, Gender::Female);
+        create_kitty(1, Gender::Female);
+        create_kitty(1, Gender::Male);
+        breed_and_wait(1, 0, 1);
+        breed_and_wait(1, 0, 1);
+        set_gender(4, Gender::Male);
+        set_gender(5, Gender::Female);
+        // kitty 6 和 7 的祖父母都是 0 和 1
+        breed_and_wait(1, 4, 2);
+        breed_and_wait(1, 5, 3);
+        set_gender(6, Gender::Female);
+        set_gender(7, Gender::Male);
+
+        assert_noop!(Kitties::breed(Origin::signed(1), 6, 7), Error::<Test>::RelatedParents);
+        assert_ok!(Kitties::breed(Origin::signed(1), 6, 3));
+    })
+}
+
+// 繁殖的权重按近亲检查范围内祖先数量的上限计算, 每往上一代翻倍
+#[test]
+fn max_ancestors_covers_inbreeding_check() {
+    new_test_ext().execute_with(|| {
+        // InbreedingDepth 为1, 检查父母和祖父母两代
+        assert_eq!(Kitties::max_ancestors(), 2 + 4);
+
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(1, Gender::Female);
+        create_kitty(1, Gender::Male);
+        create_kitty(1, Gender::Female);
+        breed_and_wait(1, 0, 1);
+        breed_and_wait(1, 2, 3);
+        set_gender(4, Gender::Male);
+        set_gender(5, Gender::Female);
+        breed_and_wait(1, 4, 5);
+        // kitty 6 的祖先为 4, 5, 0, 1, 2, 3
+        assert_eq!(Kitties::ancestors(6, 2).len() - 1, Kitties::max_ancestors() as usize);
+    })
+}
+
+// breed 后开始怀孕 到期后在 on_initialize 中出生
+#[test]
+fn breed_kitty_born_after_gestation() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(1, Gender::Female);
+        let reserved = balances::Module::<Test>::reserved_balance(1);
+
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), reserved + 5_000);
+        assert_eq!(Kitties::kitties_count(), 2);
+        assert_eq!(Kitties::kitties(0).unwrap().spouse_id(), Some(1));
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::BreedingStarted(1, 0, 1, 15))));
+
+        run_to_block(14);
+        assert!(Kitties::kitties(2).is_none());
+        run_to_block(15);
+        assert_eq!(Kitties::kitty_owner(2), Some(1));
+        assert_eq!(Kitties::kitties(2).unwrap().generation(), 1);
+        assert_eq!(Kitties::children(0, 1), vec![2]);
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::Born(1, 2, 1, Kitties::kitty_rarity(2).unwrap()))));
+    })
+}
+
+// 怀孕期和冷却期内不能繁殖 冷却时间随代数增长
+#[test]
+fn breed_kitty_fail_when_busy() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(1, Gender::Female);
+        create_kitty(1, Gender::Female);
+        create_kitty(1, Gender::Male);
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+        assert_noop!(Kitties::breed(Origin::signed(1), 0, 2), Error::<Test>::KittyBusy);
+
+        // 初代kitty 冷却 3 个区块
+        assert_eq!(Kitties::kitty_ready_at(1), 18);
+        run_to_block(17);
+        assert_noop!(Kitties::breed(Origin::signed(1), 3, 1), Error::<Test>::KittyBusy);
+        run_to_block(18);
+        set_gender(4, Gender::Male);
+        assert_ok!(Kitties::breed(Origin::signed(1), 4, 2));
+
+        // 第一代kitty 冷却 6 个区块
+        assert_eq!(Kitties::kitty_ready_at(2), 18 + 5 + 3);
+        assert_eq!(Kitties::kitty_ready_at(4), 18 + 5 + 6);
+    })
+}
+
+// 相同性别的kitty 不能繁殖
+#[test]
+fn breed_kitty_fail_when_same_gender() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(1, Gender::Male);
+        assert_noop!(Kitties::breed(Origin::signed(1), 0, 1), Error::<Test>::SameGender);
+    })
+}
+
+// 父母的顺序由性别决定 与参数顺序无关
+#[test]
+fn breed_kitty_parents_order_is_canonical() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        create_kitty(1, Gender::Female);
+        create_kitty(1, Gender::Male);
+        breed_and_wait(1, 0, 1);
+
+        assert_eq!(Kitties::kitties(0).unwrap().gender(), Gender::Female);
+        assert_eq!(Kitties::kitties(2).unwrap().parents_ids(), (Some(1), Some(0)));
+        assert_eq!(Kitties::kitty_children(1, 0), vec![2]);
+        assert_eq!(Kitties::kitty_children(0, 1), Vec::<u32>::new());
+    })
+}
+
+// 销毁kitty 删除存储并退还质押
+#[test]
+fn release_kitty_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(1, Gender::Female);
+        breed_and_wait(1, 0, 1);
+        breed_and_wait(1, 0, 1);
+        assert_eq!(Kitties::kitty_deposit(3), Some((1, 5_000)));
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 20_000);
+
+        assert_noop!(Kitties::release(Origin::signed(2), 2), Error::<Test>::NotKittyOwner);
+        assert_ok!(Kitties::release(Origin::signed(1), 2));
+
+        assert!(Kitties::kitties(2).is_none());
+        assert_eq!(Kitties::kitty_owner(2), None);
+        assert_eq!(Kitties::kitty_deposit(2), None);
+        assert_eq!(Kitties::kitties_of(&1), vec![0, 1, 3]);
+        assert_eq!(Kitties::children(0, 1), vec![3]);
+        assert_eq!(Kitties::siblings(3), Vec::<u32>::new());
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 15_000);
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::Released(1, 2))));
+    })
+}
+
+// 转移后销毁kitty 质押退还给创建者
+#[test]
+fn release_kitty_refunds_depositor() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+        assert_ok!(Kitties::create_auction(Origin::signed(2), 0, 100, 5));
+        assert_noop!(Kitties::release(Origin::signed(2), 0), Error::<Test>::KittyLocked);
+        run_to_block(16);
+
+        assert_ok!(Kitties::release(Origin::signed(2), 0));
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 0);
+        assert_eq!(Kitties::kitties_of(&2), Vec::<u32>::new());
+    })
+}
+
+// OwnerPays 模式下 质押随kitty转移给新的所有者
+#[test]
+fn transfer_moves_deposit_when_owner_pays() {
+    new_test_ext().execute_with(|| {
+        set_deposit_policy(DepositPolicy::OwnerPays);
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+
+        assert_noop!(Kitties::transfer(Origin::signed(1), 6, 0), Error::<Test>::MoneyNotEnough);
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+
+        assert_eq!(Kitties::kitty_deposit(0), Some((2, 5_000)));
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 0);
+        assert_eq!(balances::Module::<Test>::reserved_balance(2), 5_000);
+
+        assert_ok!(Kitties::set_price(Origin::signed(2), 0, Some(1_000)));
+        assert_ok!(Kitties::buy(Origin::signed(3), 0, 1_000));
+        assert_eq!(Kitties::kitty_deposit(0), Some((3, 5_000)));
+        assert_eq!(balances::Module::<Test>::reserved_balance(2), 0);
+        assert_eq!(balances::Module::<Test>::reserved_balance(3), 5_000);
+    })
+}
+
+// CreatorPays 模式下 质押留在创建者账户
+#[test]
+fn transfer_keeps_deposit_when_creator_pays() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 6, 0));
+
+        assert_eq!(Kitties::kitty_deposit(0), Some((1, 5_000)));
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 5_000);
+        assert_eq!(balances::Module::<Test>::reserved_balance(6), 0);
+    })
+}
+
+// 被授权人可以转移kitty 转移后授权被清除
+#[test]
+fn approve_and_transfer_from_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_noop!(
+            Kitties::transfer_from(Origin::signed(3), 1, 2, 0),
+            Error::<Test>::NotApproved
+        );
+        assert_noop!(Kitties::approve(Origin::signed(3), 0, Some(3)), Error::<Test>::NotApproved);
+
+        assert_ok!(Kitties::approve(Origin::signed(1), 0, Some(3)));
+        assert_eq!(Kitties::kitty_approval(0), Some(3));
+        assert_noop!(
+            Kitties::transfer_from(Origin::signed(3), 2, 4, 0),
+            Error::<Test>::NotKittyOwner
+        );
+        assert_ok!(Kitties::transfer_from(Origin::signed(3), 1, 2, 0));
+
+        assert_eq!(Kitties::kitty_owner(0), Some(2));
+        assert_eq!(Kitties::kitty_approval(0), None);
+        assert_noop!(
+            Kitties::transfer_from(Origin::signed(3), 2, 3, 0),
+            Error::<Test>::NotApproved
+        );
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::Approval(1, 0, Some(3)))));
+    })
+}
+
+// 操作员可以转移所有者的任何kitty
+#[test]
+fn operator_can_transfer_all_kitties() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::set_approval_for_all(Origin::signed(1), 3, true));
+        assert!(Kitties::is_approved_for_all(1, 3));
+
+        assert_ok!(Kitties::transfer_from(Origin::signed(3), 1, 2, 0));
+        assert_ok!(Kitties::approve(Origin::signed(3), 1, Some(4)));
+        assert_ok!(Kitties::transfer_from(Origin::signed(4), 1, 4, 1));
+
+        assert_ok!(Kitties::set_approval_for_all(Origin::signed(1), 3, false));
+        assert!(!Kitties::is_approved_for_all(1, 3));
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::ApprovalForAll(1, 3, true))));
+    })
+}
+
+// 不能直接用别人的kitty繁殖
+#[test]
+fn breed_kitty_fail_when_not_owner() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(2, Gender::Female);
+        assert_noop!(Kitties::breed(Origin::signed(2), 0, 1), Error::<Test>::NotKittyOwner);
+        assert_noop!(Kitties::breed_with(Origin::signed(2), 1, 0, 500), Error::<Test>::NoSiringOffer);
+    })
+}
+
+// 支付配种费用后繁殖 孩子属于调用者
+#[test]
+fn breed_with_siring_offer_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(2, Gender::Female);
+        assert_noop!(Kitties::offer_siring(Origin::signed(2), 0, 500), Error::<Test>::NotKittyOwner);
+        assert_ok!(Kitties::offer_siring(Origin::signed(1), 0, 500));
+        let sire_owner_free = balances::Module::<Test>::free_balance(1);
+        let breeder_free = balances::Module::<Test>::free_balance(2);
+
+        assert_ok!(Kitties::breed_with(Origin::signed(2), 1, 0, 500));
+        assert_eq!(balances::Module::<Test>::free_balance(1), sire_owner_free + 500);
+        assert_eq!(balances::Module::<Test>::free_balance(2), breeder_free - 500 - 5_000);
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::SiringFeePaid(2, 1, 0, 500))));
+
+        run_to_block(15);
+        assert_eq!(Kitties::kitty_owner(2), Some(2));
+        assert_eq!(Kitties::kitties(2).unwrap().parents_ids(), (Some(0), Some(1)));
+    })
+}
+
+// 配种服务提供者在交易打包前抬价 超过 max_fee 时繁殖失败
+#[test]
+fn breed_with_fails_when_fee_raised() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(2, Gender::Female);
+        assert_ok!(Kitties::offer_siring(Origin::signed(1), 0, 500));
+        assert_ok!(Kitties::offer_siring(Origin::signed(1), 0, 5_000));
+        assert_noop!(Kitties::breed_with(Origin::signed(2), 1, 0, 500), Error::<Test>::PriceTooHigh);
+
+        assert_ok!(Kitties::breed_with(Origin::signed(2), 1, 0, 5_000));
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::SiringFeePaid(2, 1, 0, 5_000))));
+    })
+}
+
+// 撤销或转移后配种服务被清除
+#[test]
+fn siring_offer_cleared() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::offer_siring(Origin::signed(1), 0, 500));
+        assert_ok!(Kitties::cancel_siring_offer(Origin::signed(1), 0));
+        assert_eq!(Kitties::siring_offer(0), None);
+
+        assert_ok!(Kitties::offer_siring(Origin::signed(1), 0, 500));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+        assert_eq!(Kitties::siring_offer(0), None);
+    })
+}
+
+// 突变概率为 100% 时 每个dna字节都恰好翻转一位
+#[test]
+fn breed_kitty_mutates_dna() {
+    new_test_ext().execute_with(|| {
+        set_mutation_rate(Permill::from_percent(100));
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(1, Gender::Female);
+        let father = Kitties::kitties(0).unwrap().dna_data();
+        let mother = Kitties::kitties(1).unwrap().dna_data();
+
+        assert_ok!(Kitties::breed(Origin::signed(1), 0, 1));
+        // 同一个区块内 random_value 的结果与繁殖时相同
+        let selector = Kitties::random_value(&1);
+        run_to_block(15);
+
+        let child = Kitties::kitties(2).unwrap();
+        assert!(child.is_mutated());
+        let combined = genetics::Mendelian::recombine(&father, &mother, &selector);
+        for i in 0..16 {
+            assert_eq!((combined[i] ^ child.dna_data()[i]).count_ones(), 1);
+        }
+    })
+}
+
+// 突变概率为 0 时 dna 只来自父母
+#[test]
+fn breed_kitty_without_mutation() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(1, Gender::Female);
+        breed_and_wait(1, 0, 1);
+        assert!(!Kitties::kitties(2).unwrap().is_mutated());
+    })
+}
+
+// 显性基因优先表达 两个隐性基因才表达隐性特征
+#[test]
+fn phenotype_dominant_and_recessive() {
+    let mut dna = [0u8; 16];
+    // 体色: 显性 Black(1) 与隐性 White(2), 表达 Black
+    dna[2] = phenotype::DOMINANT | 1;
+    dna[3] = 2;
+    // 眼睛: 两个显性 Amber(2) 和 Blue(1), 表达较小的 Blue
+    dna[4] = phenotype::DOMINANT | 2;
+    dna[5] = phenotype::DOMINANT | 1;
+    // 花纹: 两个隐性 Spotted(2) 和 Tuxedo(5), 表达隐性的 Spotted
+    dna[6] = 5;
+    dna[7] = 2;
+    // 配饰: 隐性 Plain(0) 与显性 Hat(3), 表达 Hat
+    dna[8] = 0;
+    dna[9] = phenotype::DOMINANT | 3;
+
+    assert_eq!(phenotype::phenotype(&dna), Phenotype {
+        body_color: BodyColor::Black,
+        eye_color: EyeColor::Blue,
+        pattern: Pattern::Spotted,
+        accessory: Accessory::Hat,
+        rarity: RarityTier::Uncommon,
+    });
+
+    // 全部是隐性基因
+    assert_eq!(phenotype::phenotype(&[0u8; 16]).rarity, RarityTier::Legendary);
+    // 全部是显性基因
+    assert_eq!(phenotype::phenotype(&[phenotype::DOMINANT; 16]).rarity, RarityTier::Common);
+}
+
+// 孩子的每对等位基因分别来自父亲和母亲
+#[test]
+fn phenotype_genes_inherited_from_both_parents() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(1, Gender::Female);
+        let father = Kitties::kitties(0).unwrap().dna_data();
+        let mother = Kitties::kitties(1).unwrap().dna_data();
+        breed_and_wait(1, 0, 1);
+
+        let child = Kitties::kitties(2).unwrap().dna_data();
+        for &gene in phenotype::TRAIT_GENES.iter() {
+            assert!(child[gene] == father[gene] || child[gene] == father[gene + 1]);
+            assert!(child[gene + 1] == mother[gene] || child[gene + 1] == mother[gene + 1]);
+        }
+        assert_eq!(Kitties::kitty_phenotype(2), Some(phenotype::phenotype(&child)));
+        assert_eq!(Kitties::kitty_phenotype(3), None);
+    })
+}
+
+// 设置元数据按字节质押 覆盖时退还之前的质押
+#[test]
+fn set_metadata_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_noop!(
+            Kitties::set_metadata(Origin::signed(2), 0, b"tom".to_vec(), vec![]),
+            Error::<Test>::NotKittyOwner
+        );
+        assert_noop!(
+            Kitties::set_metadata(Origin::signed(1), 0, vec![0; 17], vec![]),
+            Error::<Test>::NameTooLong
+        );
+        assert_noop!(
+            Kitties::set_metadata(Origin::signed(1), 0, vec![], vec![0; 65]),
+            Error::<Test>::MetadataTooLong
+        );
+
+        assert_ok!(Kitties::set_metadata(Origin::signed(1), 0, b"tom".to_vec(), b"ipfs://tom".to_vec()));
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 5_000 + 100 + 13 * 10);
+        let metadata = Kitties::kitty_metadata(0).unwrap();
+        assert_eq!(metadata.name, b"tom".to_vec());
+        assert_eq!(metadata.data, b"ipfs://tom".to_vec());
+
+        assert_ok!(Kitties::set_metadata(Origin::signed(1), 0, b"tommy".to_vec(), vec![]));
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 5_000 + 100 + 5 * 10);
+    })
+}
+
+// 清除元数据退还质押 转移后仍退还给原质押人
+#[test]
+fn clear_metadata_refunds_depositor() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_noop!(Kitties::clear_metadata(Origin::signed(1), 0), Error::<Test>::MetadataNotFound);
+        assert_ok!(Kitties::set_metadata(Origin::signed(1), 0, b"tom".to_vec(), vec![]));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 0));
+
+        assert_noop!(Kitties::clear_metadata(Origin::signed(1), 0), Error::<Test>::NotKittyOwner);
+        assert_ok!(Kitties::clear_metadata(Origin::signed(2), 0));
+        assert_eq!(Kitties::kitty_metadata(0), None);
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 5_000);
+
+        // 销毁kitty 时也会退还元数据质押
+        assert_ok!(Kitties::set_metadata(Origin::signed(2), 0, b"jerry".to_vec(), vec![]));
+        assert_eq!(balances::Module::<Test>::reserved_balance(2), 150);
+        assert_ok!(Kitties::release(Origin::signed(2), 0));
+        assert_eq!(balances::Module::<Test>::reserved_balance(2), 0);
+    })
+}
+
+// 拥有的kitty数量达到上限后不能再创建 质押不变
+#[test]
+fn create_fails_when_too_many_kitties() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        for _ in 0..MaxKittiesPerAccount::get() {
+            assert_ok!(Kitties::create(Origin::signed(1)));
+        }
+        assert_noop!(Kitties::create(Origin::signed(1)), Error::<Test>::TooManyKitties);
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 10 * 5_000);
+    })
+}
+
+// 接收方拥有的kitty数量达到上限时不能转移
+#[test]
+fn transfer_fails_when_receiver_has_too_many_kitties() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        for _ in 0..MaxKittiesPerAccount::get() {
+            assert_ok!(Kitties::create(Origin::signed(2)));
+        }
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_noop!(Kitties::transfer(Origin::signed(1), 2, 10), Error::<Test>::TooManyKitties);
+        assert_ok!(Kitties::transfer(Origin::signed(2), 1, 0));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 2, 10));
+    })
+}
+
+// 出生时所有者的kitty数量已达上限 出生失败并退还质押
+#[test]
+fn birth_fails_when_owner_has_too_many_kitties() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        let father = create_kitty(1, Gender::Male);
+        let mother = create_kitty(1, Gender::Female);
+        assert_ok!(Kitties::breed(Origin::signed(1), father, mother));
+        for _ in 2..MaxKittiesPerAccount::get() {
+            assert_ok!(Kitties::create(Origin::signed(1)));
+        }
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 11 * 5_000);
+
+        run_to_block(10 + GestationPeriod::get());
+        assert_eq!(Kitties::kitties_count(), 10);
+        assert_eq!(Kitties::owned_kitties_count(1), 10);
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 10 * 5_000);
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::BirthFailed(1, father, mother))));
+    })
+}
+
+// 旧的 OwnedKitties 和 KittiesBrother 迁移到新的存储布局
+#[test]
+fn migrate_to_v2_works() {
+    use frame_support::{Blake2_128Concat, StorageHasher, traits::OnRuntimeUpgrade};
+    use frame_support::storage::migration::{put_storage_value, get_storage_value};
+
+    new_test_ext().execute_with(|| {
+        let key_1 = Blake2_128Concat::hash(&1u64.encode());
+        let key_2 = Blake2_128Concat::hash(&2u64.encode());
+        put_storage_value(b"Kitties", b"OwnedKitties", &key_1, vec![0u32, 2]);
+        put_storage_value(b"Kitties", b"OwnedKitties", &key_2, vec![1u32]);
+        put_storage_value(b"Kitties", b"KittiesBrother", &Blake2_128Concat::hash(&2u32.encode()), Vec::<u32>::new());
+        // 与 V1 的 Kitty 编码相同: kitty_id, parents_ids, spouse_id, dna_data
+        // V1 创建和繁殖时从不设置 kitty_id
+        for kitty_id in 0u32..3 {
+            let parents = if kitty_id == 2 { (Some(0u32), Some(1u32)) } else { (None, None) };
+            let old_kitty = (None::<u32>, parents, None::<u32>, [kitty_id as u8; 16]);
+            put_storage_value(b"Kitties", b"Kitties", &Blake2_128Concat::hash(&kitty_id.encode()), old_kitty);
+        }
+        assert_eq!(Kitties::storage_version(), Releases::V1);
+
+        Kitties::on_runtime_upgrade();
+
+        assert_eq!(Kitties::storage_version(), Releases::V3);
+        assert_eq!(Kitties::kitties_of(&1), vec![0, 2]);
+        assert_eq!(Kitties::owned_kitties_count(1), 2);
+        assert_eq!(Kitties::kitties_of(&2), vec![1]);
+        assert_eq!(Kitties::owned_kitties_count(2), 1);
+        for kitty_id in 0u32..3 {
+            let kitty = Kitties::kitties(kitty_id).unwrap();
+            assert_eq!(kitty.kitty_id(), Some(kitty_id));
+            assert_eq!(kitty.dna_data(), [kitty_id as u8; 16]);
+            assert_eq!(kitty.generation(), 0);
+            assert!(!kitty.is_mutated());
+            assert_eq!(kitty.rarity(), phenotype::rarity_score(&[kitty_id as u8; 16], 0));
+        }
+        assert_eq!(Kitties::kitties(2).unwrap().parents_ids(), (Some(0), Some(1)));
+        assert_eq!(get_storage_value::<Vec<u32>>(b"Kitties", b"OwnedKitties", &key_1), None);
+        assert_eq!(
+            get_storage_value::<Vec<u32>>(b"Kitties", b"KittiesBrother", &Blake2_128Concat::hash(&2u32.encode())),
+            None
+        );
+    })
+}
+
+// 创世配置中的kitty
+#[test]
+fn genesis_config_works() {
+    let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    GenesisConfig::<Test> {
+        kitties: vec![(1, [0u8; 16]), (2, [1u8; 16]), (1, [2u8; 16])],
+        instant_breed_allowed: true,
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    sp_io::TestExternalities::new(t).execute_with(|| {
+        assert_eq!(Kitties::kitties_count(), 3);
+        assert_eq!(Kitties::kitties_of(&1), vec![0, 2]);
+        assert_eq!(Kitties::kitties_of(&2), vec![1]);
+        assert_eq!(Kitties::kitties(1).unwrap().dna_data(), [1u8; 16]);
+        assert_eq!(Kitties::kitties(1).unwrap().gender(), Gender::Female);
+        assert_eq!(Kitties::kitty_deposit(0), None);
+        assert_eq!(Kitties::storage_version(), Releases::V3);
+        assert!(Kitties::instant_breed_allowed());
+    })
+}
+
+// 繁殖时按dna和代数计算稀有度分数
+#[test]
+fn rarity_score_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        create_kitty(1, Gender::Male);
+        create_kitty(1, Gender::Female);
+        let mut dna = Kitties::kitties(0).unwrap().dna_data();
+        for &gene in phenotype::TRAIT_GENES.iter() {
+            dna[gene] &= !phenotype::DOMINANT;
+            dna[gene + 1] &= !phenotype::DOMINANT;
+        }
+        assert_eq!(phenotype::rarity_score(&dna, 0), 4 * 100 + 64);
+        assert_eq!(phenotype::rarity_score(&dna, 2), 4 * 100 + 16);
+        assert_eq!(phenotype::rarity_score(&dna, 40), 4 * 100);
+
+        breed_and_wait(1, 0, 1);
+        let child = Kitties::kitties(2).unwrap();
+        assert_eq!(Kitties::kitty_generation(2), Some(1));
+        assert_eq!(Kitties::kitty_rarity(2), Some(phenotype::rarity_score(&child.dna_data(), 1)));
+        assert_eq!(Kitties::kitty_generation(3), None);
+    })
+}
+
+// 已有的kitty迁移后计算稀有度分数
+#[test]
+fn migrate_to_v3_works() {
+    use frame_support::{Blake2_128Concat, StorageHasher, traits::OnRuntimeUpgrade};
+    use frame_support::storage::migration::put_storage_value;
+
+    new_test_ext().execute_with(|| {
+        let dna = [0u8; 16];
+        // 与 V2 的 Kitty 编码相同: kitty_id, parents_ids, spouse_id, dna_data, generation, mutated
+        let old_kitty = (Some(0u32), (None::<u32>, None::<u32>), None::<u32>, dna, 0u32, false);
+        put_storage_value(b"Kitties", b"Kitties", &Blake2_128Concat::hash(&0u32.encode()), old_kitty);
+        StorageVersion::put(Releases::V2);
+
+        Kitties::on_runtime_upgrade();
+
+        assert_eq!(Kitties::storage_version(), Releases::V3);
+        assert_eq!(Kitties::kitties(0).unwrap().dna_data(), dna);
+        assert_eq!(Kitties::kitty_rarity(0), Some(phenotype::rarity_score(&dna, 0)));
+    })
+}
+
+// 出租kitty 租期内不能转移 到期后自动归还
+#[test]
+fn lease_kitty_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_noop!(Kitties::lease(Origin::signed(1), 0, 1, 10, 1_000), Error::<Test>::LeaseOwnKitty);
+        assert_noop!(Kitties::lease(Origin::signed(1), 0, 2, 0, 1_000), Error::<Test>::InvalidLeaseDuration);
+        assert_noop!(Kitties::lease(Origin::signed(2), 0, 3, 10, 1_000), Error::<Test>::NotKittyOwner);
+        assert_ok!(Kitties::lease(Origin::signed(1), 0, 2, 10, 1_000));
+
+        assert_noop!(Kitties::accept_lease(Origin::signed(3), 0, 1_000), Error::<Test>::NotLessee);
+        assert_noop!(Kitties::accept_lease(Origin::signed(2), 0, 999), Error::<Test>::PriceTooHigh);
+        let balance_1 = balances::Module::<Test>::free_balance(1);
+        let balance_2 = balances::Module::<Test>::free_balance(2);
+        assert_ok!(Kitties::accept_lease(Origin::signed(2), 0, 1_000));
+
+        assert_eq!(balances::Module::<Test>::free_balance(1), balance_1 + 1_000);
+        assert_eq!(balances::Module::<Test>::free_balance(2), balance_2 - 1_000);
+        assert_eq!(Kitties::kitty_owner(0), Some(2));
+        assert_eq!(Kitties::kitties_of(&2), vec![0]);
+        assert_eq!(Kitties::kitties_of(&1), Vec::<u32>::new());
+        assert_eq!(Kitties::lease_offer(0), None);
+        // 质押仍由创建者承担
+        assert_eq!(Kitties::kitty_deposit(0), Some((1, 5_000)));
+
+        assert_noop!(Kitties::transfer(Origin::signed(2), 3, 0), Error::<Test>::KittyLeased);
+        assert_noop!(Kitties::set_price(Origin::signed(2), 0, Some(10)), Error::<Test>::KittyLeased);
+        assert_noop!(Kitties::release(Origin::signed(2), 0), Error::<Test>::KittyLeased);
+        assert_ok!(Kitties::approve(Origin::signed(2), 0, Some(3)));
+        assert_noop!(Kitties::transfer_from(Origin::signed(3), 2, 3, 0), Error::<Test>::KittyLeased);
+
+        run_to_block(20);
+        assert_eq!(Kitties::kitty_owner(0), Some(1));
+        assert_eq!(Kitties::kitties_of(&1), vec![0]);
+        assert_eq!(Kitties::owned_kitties_count(2), 0);
+        assert_eq!(Kitties::kitty_lease(0), None);
+        assert_eq!(Kitties::kitty_approval(0), None);
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::LeaseEnded(1, 2, 0))));
+        assert_ok!(Kitties::transfer(Origin::signed(1), 3, 0));
+    })
+}
+
+// 承租人可以用租来的kitty繁殖 孩子归承租人所有
+#[test]
+fn lessee_can_breed_with_leased_kitty() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        let father = create_kitty(1, Gender::Male);
+        let mother = create_kitty(2, Gender::Female);
+        assert_ok!(Kitties::lease(Origin::signed(1), father, 2, 100, 1_000));
+        assert_ok!(Kitties::accept_lease(Origin::signed(2), father, 1_000));
+
+        breed_and_wait(2, father, mother);
+        assert_eq!(Kitties::kitty_owner(2), Some(2));
+        assert_eq!(Kitties::kitty_owner(father), Some(2));
+    })
+}
+
+// 同一个区块结束的租约数量有上限 在不同区块接受的租约也不能指向同一个到期区块
+#[test]
+fn leases_ending_per_block_are_bounded() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create_batch(Origin::signed(1), 3));
+        assert_ok!(Kitties::lease(Origin::signed(1), 0, 2, 10, 100));
+        assert_ok!(Kitties::lease(Origin::signed(1), 1, 3, 9, 100));
+        assert_ok!(Kitties::lease(Origin::signed(1), 2, 4, 8, 100));
+
+        // 三个租约都在第20个区块到期
+        assert_ok!(Kitties::accept_lease(Origin::signed(2), 0, 100));
+        run_to_block(11);
+        assert_ok!(Kitties::accept_lease(Origin::signed(3), 1, 100));
+        run_to_block(12);
+        assert_noop!(Kitties::accept_lease(Origin::signed(4), 2, 100), Error::<Test>::TooManyLeasesEnding);
+        assert_eq!(Kitties::leases_ending_at(20), vec![0, 1]);
+
+        assert_ok!(Kitties::lease(Origin::signed(1), 2, 4, 9, 100));
+        assert_ok!(Kitties::accept_lease(Origin::signed(4), 2, 100));
+        run_to_block(21);
+        assert_eq!(Kitties::kitties_of(&1), vec![0, 1, 2]);
+    })
+}
+
+// 承租人不能修改或清除出租人设置的名字和元数据
+#[test]
+fn lessee_cannot_change_metadata() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::set_metadata(Origin::signed(1), 0, b"tom".to_vec(), b"ipfs://tom".to_vec()));
+        assert_ok!(Kitties::lease(Origin::signed(1), 0, 2, 10, 1_000));
+        assert_ok!(Kitties::accept_lease(Origin::signed(2), 0, 1_000));
+
+        assert_noop!(
+            Kitties::set_metadata(Origin::signed(2), 0, b"jerry".to_vec(), vec![]),
+            Error::<Test>::KittyLeased
+        );
+        assert_noop!(Kitties::clear_metadata(Origin::signed(2), 0), Error::<Test>::KittyLeased);
+        assert_eq!(Kitties::kitty_metadata(0).unwrap().name, b"tom".to_vec());
+
+        // 租期结束后出租人可以修改
+        run_to_block(20);
+        assert_ok!(Kitties::clear_metadata(Origin::signed(1), 0));
+    })
+}
+
+// 批量创建kitty 一次性质押 失败时全部回滚
+#[test]
+fn create_batch_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_noop!(Kitties::create_batch(Origin::signed(1), 6), Error::<Test>::BatchTooLarge);
+        assert_noop!(Kitties::create_batch(Origin::signed(6), 1), Error::<Test>::MoneyNotEnough);
+
+        assert_ok!(Kitties::create_batch(Origin::signed(1), 3));
+        assert_eq!(Kitties::kitties_of(&1), vec![0, 1, 2]);
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 15_000);
+        assert_eq!(Kitties::kitty_deposit(2), Some((1, 5_000)));
+        assert_ne!(Kitties::kitties(0).unwrap().dna_data(), Kitties::kitties(1).unwrap().dna_data());
+
+        // 超过拥有上限时全部回滚
+        assert_ok!(Kitties::create_batch(Origin::signed(1), 5));
+        assert_noop!(Kitties::create_batch(Origin::signed(1), 3), Error::<Test>::TooManyKitties);
+        assert_eq!(Kitties::kitties_count(), 8);
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 40_000);
+    })
+}
+
+// 批量转移kitty 失败时全部回滚
+#[test]
+fn transfer_batch_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create_batch(Origin::signed(1), 3));
+        assert_ok!(Kitties::transfer_batch(Origin::signed(1), vec![(2, 0), (3, 1)]));
+        assert_eq!(Kitties::kitty_owner(0), Some(2));
+        assert_eq!(Kitties::kitty_owner(1), Some(3));
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::Transfered(1, 3, 1))));
+
+        assert_noop!(
+            Kitties::transfer_batch(Origin::signed(1), vec![(2, 2), (2, 1)]),
+            Error::<Test>::NotKittyOwner
+        );
+        assert_eq!(Kitties::kitty_owner(2), Some(1));
+        assert_noop!(
+            Kitties::transfer_batch(Origin::signed(1), vec![(2, 2); 6]),
+            Error::<Test>::BatchTooLarge
+        );
+    })
+}
+
+// 融合两只kitty 保留一份质押 退还另一份
+#[test]
+fn fuse_kitties_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create(Origin::signed(2)));
+        assert_noop!(Kitties::fuse(Origin::signed(1), 0, 0), Error::<Test>::RequireDifferentParent);
+        assert_noop!(Kitties::fuse(Origin::signed(1), 0, 2), Error::<Test>::NotKittyOwner);
+        assert_ok!(Kitties::set_metadata(Origin::signed(1), 1, b"tom".to_vec(), vec![]));
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 10_000 + 130);
+
+        assert_ok!(Kitties::fuse(Origin::signed(1), 0, 1));
+
+        assert!(Kitties::kitties(0).is_none());
+        assert!(Kitties::kitties(1).is_none());
+        let fused = Kitties::kitties(3).unwrap();
+        assert_eq!(fused.parents_ids(), (Some(0), Some(1)));
+        assert_eq!(fused.generation(), 1);
+        assert!(Kitties::is_fused(3));
+        assert!(!Kitties::is_fused(2));
+        assert_eq!(Kitties::kitties_of(&1), vec![3]);
+        assert_eq!(Kitties::kitty_deposit(3), Some((1, 5_000)));
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 5_000);
+        // 融合不是繁殖, 不记录为孩子
+        assert_eq!(Kitties::children(0, 1), Vec::<u32>::new());
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::Fused(1, 0, 1, 3))));
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::Created(1, 3, 1, fused.rarity()))));
+    })
+}
+
+// 融合而来的kitty没有兄弟姐妹 但与被融合kitty的孩子仍是近亲
+#[test]
+fn fused_kitty_has_no_siblings() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        let father = create_kitty(1, Gender::Male);
+        let mother = create_kitty(1, Gender::Female);
+        breed_and_wait(1, father, mother);
+        let child = 2;
+        assert_eq!(Kitties::kitty_owner(child), Some(1));
+
+        assert_ok!(Kitties::fuse(Origin::signed(1), father, mother));
+        let fused = 3;
+        assert!(Kitties::is_fused(fused));
+        assert_eq!(Kitties::siblings(fused), Vec::<u32>::new());
+        assert_eq!(Kitties::siblings(child), Vec::<u32>::new());
+
+        if Kitties::kitties(fused).unwrap().gender() == Kitties::kitties(child).unwrap().gender() {
+            set_gender(fused, match Kitties::kitties(child).unwrap().gender() {
+                Gender::Male => Gender::Female,
+                Gender::Female => Gender::Male,
+            });
+        }
+        assert_noop!(Kitties::breed(Origin::signed(1), fused, child), Error::<Test>::RelatedParents);
+    })
+}
+
+// 战斗属性由dna确定 威力大的一方获胜概率更大
+#[test]
+fn battle_stats_work() {
+    let mut dna = [0u8; 16];
+    dna[stats::ATTACK_GENE] = 100;
+    dna[stats::ATTACK_GENE + 1] = 100;
+    dna[stats::DEFENSE_GENE] = 200;
+    dna[stats::SPEED_GENE + 1] = 200;
+    let strong = stats::stats(&dna);
+    assert_eq!(strong, Stats { attack: 200, defense: 200, speed: 200 });
+
+    let weak = stats::stats(&[0u8; 16]);
+    // strong 的威力为 200 + 200 / 4 + 1 = 251, weak 的威力为 1
+    assert!(stats::battle(&strong, &weak, 0));
+    assert!(stats::battle(&strong, &weak, 250));
+    assert!(!stats::battle(&strong, &weak, 251));
+    assert!(stats::battle(&strong, &weak, 252));
+    assert!(stats::battle(&weak, &strong, 0));
+    assert!(!stats::battle(&weak, &strong, 1));
+}
+
+// 锦标赛期间kitty被锁定 结束后胜者赢得所有报名费
+#[test]
+fn tournament_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        for who in 1..=4 {
+            create_kitty(who, Gender::Male);
+        }
+        assert_noop!(Kitties::create_tournament(Origin::signed(1), 100, 1, 5), Error::<Test>::InvalidTournamentSize);
+        assert_noop!(Kitties::create_tournament(Origin::signed(1), 100, 9, 5), Error::<Test>::InvalidTournamentSize);
+        assert_noop!(Kitties::create_tournament(Origin::signed(1), 100, 4, 0), Error::<Test>::InvalidTournamentStart);
+        assert_ok!(Kitties::create_tournament(Origin::signed(1), 100, 4, 5));
+        assert_eq!(Kitties::tournaments(0).unwrap().start, 15);
+        // 创建者质押 TournamentDeposit
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 6_000);
+
+        assert_noop!(Kitties::enter_tournament(Origin::signed(1), 1, 0), Error::<Test>::InvalidTournamentId);
+        assert_noop!(Kitties::enter_tournament(Origin::signed(2), 0, 0), Error::<Test>::NotKittyOwner);
+        for who in 1..=4 {
+            assert_ok!(Kitties::enter_tournament(Origin::signed(who), 0, who as u32 - 1));
+        }
+        let kitty_id = create_kitty(5, Gender::Male);
+        assert_noop!(Kitties::enter_tournament(Origin::signed(5), 0, kitty_id), Error::<Test>::TournamentFull);
+        assert_eq!(balances::Module::<Test>::reserved_balance(2), 5_100);
+
+        // 参赛的kitty不能转移和繁殖
+        assert_eq!(Kitties::kitty_tournament(0), Some(0));
+        assert_noop!(Kitties::transfer(Origin::signed(1), 5, 0), Error::<Test>::KittyLocked);
+        let mother = create_kitty(1, Gender::Female);
+        assert_noop!(Kitties::breed(Origin::signed(1), 0, mother), Error::<Test>::KittyLocked);
+
+        let free: Vec<u64> = (1..=4).map(|who| balances::Module::<Test>::free_balance(who)).collect();
+        run_to_block(15);
+        assert_noop!(Kitties::enter_tournament(Origin::signed(5), 0, kitty_id), Error::<Test>::RegistrationClosed);
+
+        // 第15个区块进行两场半决赛, 第16个区块进行决赛
+        run_to_block(17);
+        assert!(Kitties::tournaments(0).is_none());
+        let battles = System::events().iter().filter(|r| match r.event {
+            TestEvent::kitties_event(Event::<Test>::BattleFought(..)) => true,
+            _ => false,
+        }).count();
+        assert_eq!(battles, 3);
+        let (winner, winner_kitty, pot) = System::events().iter().find_map(|r| match r.event {
+            TestEvent::kitties_event(Event::<Test>::TournamentWon(0, winner, kitty_id, pot)) => Some((winner, kitty_id, pot)),
+            _ => None,
+        }).unwrap();
+        assert_eq!(pot, 400);
+        assert_eq!(Kitties::kitty_owner(winner_kitty), Some(winner));
+        for who in 1..=4 {
+            let won = if who == winner { 400 } else { 0 };
+            let refund = if who == 1 { 1_000 } else { 0 };
+            assert_eq!(balances::Module::<Test>::free_balance(who), free[who as usize - 1] + won + refund);
+            assert_eq!(Kitties::kitty_tournament(who as u32 - 1), None);
+        }
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 5_000);
+        assert_eq!(balances::Module::<Test>::reserved_balance(2), 5_000);
+        assert_ok!(Kitties::transfer(Origin::signed(1), 5, 0));
+    })
+}
+
+// 参赛kitty不足两只时取消锦标赛 退还报名费
+#[test]
+fn tournament_cancelled_without_enough_entrants() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_ok!(Kitties::create(Origin::signed(1)));
+        assert_ok!(Kitties::create_tournament(Origin::signed(2), 100, 4, 5));
+        assert_ok!(Kitties::enter_tournament(Origin::signed(1), 0, 0));
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 5_100);
+        assert_eq!(balances::Module::<Test>::reserved_balance(2), 1_000);
+
+        run_to_block(16);
+        assert!(Kitties::tournaments(0).is_none());
+        assert_eq!(Kitties::kitty_tournament(0), None);
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 5_000);
+        assert_eq!(balances::Module::<Test>::reserved_balance(2), 0);
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::TournamentCancelled(0))));
+    })
+}
+
+// 同一个区块进行的锦标赛数量有上限, 后续轮次顺延到有空位的区块
+#[test]
+fn tournaments_per_block_are_bounded() {
+    use frame_support::traits::OnInitialize;
+
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        assert_noop!(Kitties::create_tournament(Origin::signed(6), 0, 2, 1), Error::<Test>::MoneyNotEnough);
+        for who in 1..=4 {
+            create_kitty(who, Gender::Male);
+        }
+        // 锦标赛0 在第11个区块开赛, 第12个区块决赛
+        assert_ok!(Kitties::create_tournament(Origin::signed(1), 0, 4, 1));
+        // 第12个区块已经安排了两场锦标赛
+        assert_ok!(Kitties::create_tournament(Origin::signed(1), 0, 2, 2));
+        assert_ok!(Kitties::create_tournament(Origin::signed(1), 0, 2, 2));
+        assert_noop!(Kitties::create_tournament(Origin::signed(1), 0, 2, 2), Error::<Test>::TooManyTournaments);
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 5_000 + 3_000);
+        for who in 1..=4 {
+            assert_ok!(Kitties::enter_tournament(Origin::signed(who), 0, who as u32 - 1));
+        }
+
+        // 对战的权重在 on_initialize 中计入
+        assert!(Kitties::on_initialize(12) > Kitties::on_initialize(13));
+        run_to_block(12);
+        assert_eq!(Kitties::tournaments_at(12), vec![1, 2]);
+        assert_eq!(Kitties::tournaments_at(13), vec![0]);
+        run_to_block(14);
+        assert!(Kitties::tournaments(0).is_none());
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 5_000);
+    })
+}
+
+// 关闭一步繁殖后 需要先提交承诺 等待 RevealDelay 个区块后揭示
+#[test]
+fn commit_reveal_breed_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        set_allow_instant_breed(false);
+        let father = create_kitty(1, Gender::Male);
+        let mother = create_kitty(1, Gender::Female);
+        assert_noop!(Kitties::breed(Origin::signed(1), father, mother), Error::<Test>::InstantBreedDisabled);
+        assert_noop!(Kitties::breed_with(Origin::signed(1), father, mother, 500), Error::<Test>::InstantBreedDisabled);
+
+        let salt = [7u8; 32];
+        assert_noop!(Kitties::reveal_breed(Origin::signed(1), father, mother, salt, 0), Error::<Test>::NoBreedCommitment);
+        let commitment = Kitties::breed_commitment_hash(father, mother, &salt);
+        assert_ok!(Kitties::commit_breed(Origin::signed(1), commitment));
+        assert_eq!(Kitties::breed_commitment(1), Some((commitment, 12, 500)));
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 10_000 + 500);
+        assert_noop!(Kitties::reveal_breed(Origin::signed(1), father, mother, salt, 0), Error::<Test>::RevealTooEarly);
+
+        run_to_block(12);
+        assert_noop!(Kitties::reveal_breed(Origin::signed(1), father, mother, [8u8; 32], 0), Error::<Test>::InvalidReveal);
+        assert_noop!(Kitties::reveal_breed(Origin::signed(1), mother, father, salt, 0), Error::<Test>::InvalidReveal);
+        assert_ok!(Kitties::reveal_breed(Origin::signed(1), father, mother, salt, 0));
+        assert_eq!(Kitties::breed_commitment(1), None);
+        // 保证金退还, 新kitty的质押在出生前已经质押
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 15_000);
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::BreedingStarted(1, father, mother, 17))));
+
+        run_to_block(17);
+        assert_eq!(Kitties::kitty_owner(2), Some(1));
+        assert_eq!(Kitties::kitties(2).unwrap().parents_ids(), (Some(father), Some(mother)));
+    })
+}
+
+// 一步繁殖的开关保存在链上 只有 root 可以修改
+#[test]
+fn set_instant_breed_allowed_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        let father = create_kitty(1, Gender::Male);
+        let mother = create_kitty(1, Gender::Female);
+        assert_noop!(Kitties::set_instant_breed_allowed(Origin::signed(1), false), DispatchError::BadOrigin);
+
+        assert_ok!(Kitties::set_instant_breed_allowed(Origin::root(), false));
+        assert!(!Kitties::instant_breed_allowed());
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::InstantBreedAllowedSet(false))));
+        assert_noop!(Kitties::breed(Origin::signed(1), father, mother), Error::<Test>::InstantBreedDisabled);
+
+        assert_ok!(Kitties::set_instant_breed_allowed(Origin::root(), true));
+        assert_ok!(Kitties::breed(Origin::signed(1), father, mother));
+    })
+}
+
+// 进入下一个区块并改变随机数来源, 模拟真实链上区块哈希的变化
+fn next_block_with_new_randomness(n: u64) {
+    use frame_support::traits::OnInitialize;
+
+    run_to_block(n);
+    System::set_parent_hash(sp_core::H256::repeat_byte(n as u8));
+    pallet_randomness_collective_flip::Module::<Test>::on_initialize(n);
+}
+
+// 同一个承诺无论在哪个区块揭示 得到的种子都相同
+#[test]
+fn reveal_seeds_fixed_at_commit() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        let salt = [7u8; 32];
+        assert_ok!(Kitties::commit_breed(Origin::signed(1), Kitties::breed_commitment_hash(0, 1, &salt)));
+        let (_, reveal_at, _) = Kitties::breed_commitment(1).unwrap();
+        assert_eq!(reveal_at, 12);
+        assert_eq!(Kitties::revealed_seeds(&1, reveal_at, &salt), None);
+
+        for n in 11..=13 {
+            next_block_with_new_randomness(n);
+        }
+        let seeds = Kitties::revealed_seeds(&1, reveal_at, &salt);
+        assert!(seeds.is_some());
+        let random = <Test as Trait>::Randomness::random(b"kitties/reveal");
+
+        for n in 14..=15 {
+            next_block_with_new_randomness(n);
+        }
+        // 当前区块的随机数已经变化, 揭示使用的种子不变
+        assert_ne!(<Test as Trait>::Randomness::random(b"kitties/reveal"), random);
+        assert_eq!(Kitties::revealed_seeds(&1, reveal_at, &salt), seeds);
+        // 盐不同种子也不同
+        assert_ne!(Kitties::revealed_seeds(&1, reveal_at, &[8u8; 32]), seeds);
+    })
+}
+
+// 超过揭示期后承诺失效并没收保证金 需要重新提交
+#[test]
+fn breed_commitment_expires() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        set_allow_instant_breed(false);
+        let father = create_kitty(1, Gender::Male);
+        let mother = create_kitty(1, Gender::Female);
+        let salt = [7u8; 32];
+        let commitment = Kitties::breed_commitment_hash(father, mother, &salt);
+        assert_ok!(Kitties::commit_breed(Origin::signed(1), commitment));
+        let total = balances::Module::<Test>::total_balance(&1);
+
+        // 揭示区块为12, 揭示期到第17个区块为止
+        run_to_block(17);
+        assert!(Kitties::breed_commitment(1).is_some());
+        run_to_block(18);
+        assert_eq!(Kitties::breed_commitment(1), None);
+        assert_noop!(Kitties::reveal_breed(Origin::signed(1), father, mother, salt, 0), Error::<Test>::NoBreedCommitment);
+        assert_eq!(Kitties::reveal_randomness(12), None);
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 10_000);
+        assert_eq!(balances::Module::<Test>::total_balance(&1), total - 500);
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::BreedBondForfeited(1, 500))));
+
+        assert_ok!(Kitties::commit_breed(Origin::signed(1), commitment));
+        assert_eq!(Kitties::breed_commitment(1), Some((commitment, 20, 500)));
+        run_to_block(20);
+        assert_ok!(Kitties::reveal_breed(Origin::signed(1), father, mother, salt, 0));
+    })
+}
+
+// 覆盖未揭示的承诺会没收之前的保证金 不能免费重新抽取dna
+#[test]
+fn overwriting_breed_commitment_forfeits_bond() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        set_allow_instant_breed(false);
+        let father = create_kitty(1, Gender::Male);
+        let mother = create_kitty(1, Gender::Female);
+        assert_noop!(
+            Kitties::commit_breed(Origin::signed(6), Kitties::breed_commitment_hash(father, mother, &[0u8; 32])),
+            Error::<Test>::MoneyNotEnough
+        );
+        assert_ok!(Kitties::commit_breed(Origin::signed(1), Kitties::breed_commitment_hash(father, mother, &[7u8; 32])));
+        let total = balances::Module::<Test>::total_balance(&1);
+
+        run_to_block(12);
+        let commitment = Kitties::breed_commitment_hash(father, mother, &[8u8; 32]);
+        assert_ok!(Kitties::commit_breed(Origin::signed(1), commitment));
+        assert_eq!(Kitties::breed_commitment(1), Some((commitment, 14, 500)));
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 10_000 + 500);
+        assert_eq!(balances::Module::<Test>::total_balance(&1), total - 500);
+
+        // 第一个承诺的过期记录不会影响新的承诺
+        run_to_block(18);
+        assert_eq!(Kitties::breed_commitment(1), Some((commitment, 14, 500)));
+        assert_ok!(Kitties::reveal_breed(Origin::signed(1), father, mother, [8u8; 32], 0));
+        assert_eq!(balances::Module::<Test>::reserved_balance(1), 15_000);
+    })
+}
+
+// 揭示繁殖时可以使用别人提供配种服务的kitty 并支付配种费用
+#[test]
+fn reveal_breed_with_siring_offer_works() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        set_allow_instant_breed(false);
+        let sire = create_kitty(1, Gender::Male);
+        let mother = create_kitty(2, Gender::Female);
+        let salt = [7u8; 32];
+        assert_ok!(Kitties::commit_breed(Origin::signed(2), Kitties::breed_commitment_hash(mother, sire, &salt)));
+        run_to_block(12);
+        assert_noop!(Kitties::reveal_breed(Origin::signed(2), mother, sire, salt, 100), Error::<Test>::NoSiringOffer);
+
+        assert_ok!(Kitties::offer_siring(Origin::signed(1), sire, 100));
+        let balance_1 = balances::Module::<Test>::free_balance(1);
+        assert_ok!(Kitties::reveal_breed(Origin::signed(2), mother, sire, salt, 100));
+        assert_eq!(balances::Module::<Test>::free_balance(1), balance_1 + 100);
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::SiringFeePaid(2, 1, sire, 100))));
+    })
+}
+
+// 揭示前配种费用被抬高 超过 max_fee 时揭示失败 承诺保留
+#[test]
+fn reveal_breed_fails_when_fee_raised() {
+    new_test_ext().execute_with(|| {
+        run_to_block(10);
+        set_allow_instant_breed(false);
+        let sire = create_kitty(1, Gender::Male);
+        let mother = create_kitty(2, Gender::Female);
+        let salt = [7u8; 32];
+        assert_ok!(Kitties::offer_siring(Origin::signed(1), sire, 100));
+        assert_ok!(Kitties::commit_breed(Origin::signed(2), Kitties::breed_commitment_hash(mother, sire, &salt)));
+        run_to_block(12);
+
+        assert_ok!(Kitties::offer_siring(Origin::signed(1), sire, 1_000));
+        assert_noop!(Kitties::reveal_breed(Origin::signed(2), mother, sire, salt, 100), Error::<Test>::PriceTooHigh);
+        assert!(Kitties::breed_commitment(2).is_some());
+
+        assert_ok!(Kitties::reveal_breed(Origin::signed(2), mother, sire, salt, 1_000));
+        assert!(System::events().iter().any(|r| r.event ==
+            TestEvent::kitties_event(Event::<Test>::SiringFeePaid(2, 1, sire, 1_000))));
+    })
+}
+
+// 均匀交叉 每一位按 selector 取自父亲或母亲
+#[test]
+fn uniform_crossover_works() {
+    use genetics::UniformCrossover;
+
+    let father = [0xaau8; 16];
+    let mother = [0x55u8; 16];
+    assert_eq!(UniformCrossover::recombine(&father, &mother, &[0xff; 16]), father);
+    assert_eq!(UniformCrossover::recombine(&father, &mother, &[0; 16]), mother);
+    assert_eq!(UniformCrossover::recombine(&father, &mother, &[0xf0; 16]), [0xa5; 16]);
+    assert_eq!(UniformCrossover::create(&[7; 16]), [7; 16]);
+}
+
+// 单点交叉 切点之前的位取自父亲 之后的取自母亲
+#[test]
+fn single_point_crossover_works() {
+    use genetics::SinglePointCrossover;
+
+    let father = [0xffu8; 16];
+    let mother = [0u8; 16];
+    let mut expected = [0u8; 16];
+    expected[0] = 0x80;
+    assert_eq!(SinglePointCrossover::recombine(&father, &mother, &[0; 16]), expected);
+    // 只有 selector 的第一个字节决定切点
+    let mut selector = [0xffu8; 16];
+    selector[0] = 19;
+    let mut expected = [0u8; 16];
+    expected[0] = 0xff;
+    expected[1] = 0xff;
+    expected[2] = 0xf0;
+    assert_eq!(SinglePointCrossover::recombine(&father, &mother, &selector), expected);
+    let mut expected = [0xffu8; 16];
+    expected[15] = 0xfe;
+    let mut selector = [0u8; 16];
+    selector[0] = 126;
+    assert_eq!(SinglePointCrossover::recombine(&father, &mother, &selector), expected);
+    // 切点在 1 到 127 之间, 孩子总是同时拥有父母双方的基因
+    selector[0] = 127;
+    assert_eq!(SinglePointCrossover::recombine(&father, &mother, &selector)[0], 0x80);
+}
+
+// 孟德尔遗传 特征基因的等位基因分别取自父母 其余字节均匀交叉
+#[test]
+fn mendelian_genetics_works() {
+    use genetics::Mendelian;
+
+    let mut father = [0u8; 16];
+    let mut mother = [0u8; 16];
+    for i in 0..16 {
+        father[i] = i as u8;
+        mother[i] = 100 + i as u8;
+    }
+
+    let child = Mendelian::recombine(&father, &mother, &[0; 16]);
+    for &gene in phenotype::TRAIT_GENES.iter() {
+        assert_eq!(child[gene], father[gene]);
+        assert_eq!(child[gene + 1], mother[gene]);
+    }
+    assert_eq!(child[0], mother[0]);
+    assert_eq!(child[15], mother[15]);
+
+    let child = Mendelian::recombine(&father, &mother, &[0xff; 16]);
+    for &gene in phenotype::TRAIT_GENES.iter() {
+        assert_eq!(child[gene], father[gene + 1]);
+        assert_eq!(child[gene + 1], mother[gene + 1]);
+    }
+    assert_eq!(child[0], father[0]);
+    assert_eq!(child[15], father[15]);
+}
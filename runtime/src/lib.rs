@@ -68,6 +68,9 @@ pub type Hash = sp_core::H256;
 /// Digest item type.
 pub type DigestItem = generic::DigestItem<Hash>;
 
+/// Index of a kitty.
+pub type KittyIndex = u32;
+
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
 /// of data like extrinsics, allowing for them to continue syncing the network through upgrades
@@ -275,10 +278,71 @@ impl pallet_poe::Trait for Runtime {
     type MaxClaimLength = MaxClaimLength;
 }
 
+parameter_types! {
+    // 创建kitty 的时候，需要质押的代币
+    pub const NewKittyReserve: Balance = 5_000;
+    // 质押随kitty转移, 由当前所有者承担
+    pub const KittyDepositPolicy: pallet_kitties::DepositPolicy = pallet_kitties::DepositPolicy::OwnerPays;
+    // 与 nicks 类似, 名字和元数据按字节收取质押
+    pub const KittyMetadataDepositBase: Balance = 100;
+    pub const KittyMetadataDepositPerByte: Balance = 10;
+    // Maximum bounds on storage are important to secure your chain.
+    pub const MaxKittyNameLength: u32 = 32;
+    pub const MaxKittyMetadataLength: u32 = 256;
+    // 每个账号最多拥有的kitty数量
+    pub const MaxKittiesPerAccount: u32 = 1_000;
+    // 批量创建和转移一次最多处理的kitty数量
+    pub const MaxKittyBatchSize: u32 = 100;
+    // 每个区块最多结算的拍卖数量
+    pub const MaxKittyAuctionsPerBlock: u32 = 50;
+    // 每个区块最多结束的租约数量
+    pub const MaxKittyLeasesPerBlock: u32 = 50;
+    // 一场锦标赛最多六轮
+    pub const MaxKittyTournamentSize: u32 = 64;
+    // 每个区块最多进行的锦标赛数量, 创建锦标赛需要质押
+    pub const MaxKittyTournamentsPerBlock: u32 = 10;
+    pub const KittyTournamentDeposit: Balance = 10_000;
+    // 禁止父母/子女, 兄弟姐妹和堂表兄弟姐妹之间繁殖
+    pub const InbreedingDepth: u32 = 1;
+    // 怀孕期一个小时, 初代kitty出生后冷却十分钟
+    pub const GestationPeriod: BlockNumber = HOURS;
+    pub const BreedCooldown: BlockNumber = 10 * MINUTES;
+    // 每个dna字节有 0.5% 的概率突变
+    pub const MutationRate: Permill = Permill::from_parts(5_000);
+    // 提交繁殖承诺一分钟后才能揭示, 之后一个小时内有效
+    pub const RevealDelay: BlockNumber = MINUTES;
+    pub const RevealWindow: BlockNumber = HOURS;
+    // 提交繁殖承诺的保证金, 不揭示则没收
+    pub const KittyBreedBond: Balance = 1_000;
+}
+
 impl pallet_kitties::Trait for Runtime {
 	type Event = Event;
     type Randomness = RandomnessCollectiveFlip;
-    type KittyIndex = u32;
+    type KittyIndex = KittyIndex;
+    type NewKittyReserve = NewKittyReserve;
+    type Currency = Balances;
+    type DepositPolicy = KittyDepositPolicy;
+    type MetadataDepositBase = KittyMetadataDepositBase;
+    type MetadataDepositPerByte = KittyMetadataDepositPerByte;
+    type MaxNameLength = MaxKittyNameLength;
+    type MaxMetadataLength = MaxKittyMetadataLength;
+    type MaxKittiesPerAccount = MaxKittiesPerAccount;
+    type MaxBatchSize = MaxKittyBatchSize;
+    type MaxAuctionsPerBlock = MaxKittyAuctionsPerBlock;
+    type MaxLeasesPerBlock = MaxKittyLeasesPerBlock;
+    type MaxTournamentSize = MaxKittyTournamentSize;
+    type MaxTournamentsPerBlock = MaxKittyTournamentsPerBlock;
+    type TournamentDeposit = KittyTournamentDeposit;
+    type InbreedingDepth = InbreedingDepth;
+    type GestationPeriod = GestationPeriod;
+    type BreedCooldown = BreedCooldown;
+    type MutationRate = MutationRate;
+    type RevealDelay = RevealDelay;
+    type RevealWindow = RevealWindow;
+    type BreedBond = KittyBreedBond;
+    type Genetics = pallet_kitties::genetics::Mendelian;
+    type WeightInfo = pallet_kitties::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -333,7 +397,7 @@ construct_runtime!(
 		// Include the custom logic from the template pallet in the runtime.
 		TemplateModule: pallet_template::{Module, Call, Storage, Event<T>},
         PoeModule: pallet_poe::{Module, Call, Storage, Event<T>},
-        KittiesModule: pallet_kitties::{Module, Call, Storage, Event<T>},
+        KittiesModule: pallet_kitties::{Module, Call, Storage, Event<T>, Config<T>},
 	}
 );
 
@@ -494,6 +558,43 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_rpc_runtime_api::KittiesApi<Block, AccountId, KittyIndex, pallet_kitties::Phenotype> for Runtime {
+		fn kitty(id: KittyIndex) -> Option<pallet_kitties_rpc_runtime_api::KittyInfo<AccountId, KittyIndex>> {
+			let kitty = KittiesModule::kitties(id)?;
+			let owner = KittiesModule::kitty_owner(id)?;
+			Some(pallet_kitties_rpc_runtime_api::KittyInfo {
+				id,
+				owner,
+				dna: kitty.dna_data().to_vec(),
+				parents: kitty.parents_ids(),
+				spouse: kitty.spouse_id(),
+				generation: kitty.generation(),
+				rarity: kitty.rarity(),
+				fused: KittiesModule::is_fused(id),
+			})
+		}
+
+		fn kitties_of(owner: AccountId) -> Vec<KittyIndex> {
+			KittiesModule::kitties_of(&owner)
+		}
+
+		fn kitties_count() -> KittyIndex {
+			KittiesModule::kitties_count()
+		}
+
+		fn children(a: KittyIndex, b: KittyIndex) -> Vec<KittyIndex> {
+			KittiesModule::children(a, b)
+		}
+
+		fn siblings(id: KittyIndex) -> Vec<KittyIndex> {
+			KittiesModule::siblings(id)
+		}
+
+		fn phenotype(id: KittyIndex) -> Option<pallet_kitties::Phenotype> {
+			KittiesModule::kitty_phenotype(id)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -523,6 +624,7 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+			add_benchmark!(params, batches, pallet_kitties, KittiesModule);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)